@@ -0,0 +1,34 @@
+/* C API for sequex, enabled with the `ffi` feature. See src/ffi.rs for documentation. */
+#ifndef SEQUEX_H
+#define SEQUEX_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define SEQUEX_OK 0
+#define SEQUEX_WOULD_BLOCK 1
+#define SEQUEX_POISONED (-1)
+#define SEQUEX_INVALID (-2)
+#define SEQUEX_TOO_MANY_TICKETS (-3)
+#define SEQUEX_OUT_OF_MEMORY (-4)
+#define SEQUEX_PANICKED (-5)
+
+typedef struct SequexTicket SequexTicket;
+typedef struct SequexGuard SequexGuard;
+
+int32_t sequex_create(void *data, uint64_t num_tickets, SequexTicket **tickets);
+void sequex_ticket_free(SequexTicket *ticket);
+int32_t sequex_lock(const SequexTicket *ticket, SequexGuard **guard);
+int32_t sequex_try_lock(const SequexTicket *ticket, SequexGuard **guard);
+void sequex_unlock(SequexGuard *guard);
+void *sequex_guard_data(const SequexGuard *guard);
+void sequex_poison(const SequexTicket *ticket);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
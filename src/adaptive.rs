@@ -0,0 +1,81 @@
+use crate::{wait::MAX_BACKOFF, Shared, WaitStrategy};
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+// Turns held for at most this long, in nanoseconds, are waited for by spinning alone.
+const SHORT: u64 = 1_000;
+
+// Turns held for at least this long, in nanoseconds, are waited for by parking straight away.
+const LONG: u64 = 100_000;
+
+// How long a waiter spins for turns that are short, before it assumes the holder was
+// descheduled and parks after all.
+const MAX_SPIN: Duration = Duration::from_micros(100);
+
+// A moving average of how long the turns of a sequence are held for, measured from when the
+// turn in progress started, in nanoseconds since `epoch`, under `WaitStrategy::Adaptive`.
+pub(crate) struct HoldTimes {
+    epoch: Instant,
+    started: AtomicU64,
+    average: AtomicU64,
+}
+
+impl HoldTimes {
+    // Returns how long to spin before parking, and how long to park for first, in
+    // microseconds. Until a turn has been measured, this waits like `WaitStrategy::Backoff`.
+    pub(crate) fn plan(&self) -> (Duration, u64) {
+        let average = self.average.load(Ordering::Relaxed);
+        let first_park = (average / 4_000).clamp(1, MAX_BACKOFF);
+        match average {
+            0 => (Duration::ZERO, 100),
+            1..=SHORT => (MAX_SPIN, first_park),
+            LONG.. => (Duration::ZERO, first_park),
+            _ => (Duration::from_nanos(average), first_park),
+        }
+    }
+
+    fn now(&self) -> u64 {
+        u64::try_from(self.epoch.elapsed().as_nanos()).unwrap_or(u64::MAX)
+    }
+}
+
+impl Default for HoldTimes {
+    fn default() -> Self {
+        Self {
+            epoch: Instant::now(),
+            started: AtomicU64::new(0),
+            average: AtomicU64::new(0),
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    // Start timing the turn that has just been acquired.
+    pub(crate) fn time_acquire(&self) {
+        if self.config.wait_strategy == WaitStrategy::Adaptive {
+            let hold_times = &self.hold_times;
+            hold_times
+                .started
+                .store(hold_times.now(), Ordering::Relaxed);
+        }
+    }
+
+    // Fold the held turn into the average as it ends. Only the holder updates the average,
+    // so it needs no read-modify-write.
+    pub(crate) fn time_release(&self) {
+        if self.config.wait_strategy == WaitStrategy::Adaptive {
+            let hold_times = &self.hold_times;
+            let held = hold_times
+                .now()
+                .saturating_sub(hold_times.started.load(Ordering::Relaxed))
+                .max(1);
+            let average = match hold_times.average.load(Ordering::Relaxed) {
+                0 => held,
+                average => average - average / 8 + held / 8,
+            };
+            hold_times.average.store(average.max(1), Ordering::Relaxed);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{Guard, Shared};
+use std::{
+    any::Any,
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+// The message the holder of the lock attached for the next turn, and the one it received
+// from the turn before it. Nothing is passed on until a message is first sent.
+#[derive(Default)]
+pub(crate) struct Baton {
+    used: AtomicBool,
+    state: Mutex<BatonState>,
+}
+
+#[derive(Default)]
+struct BatonState {
+    sent: Option<Box<dyn Any + Send>>,
+    received: Option<Box<dyn Any + Send>>,
+}
+
+impl<T> Guard<'_, T> {
+    /// Release the lock and attach `message` for the next turn, whose guard can take it with
+    /// [Guard::baton], so stages can tell each other about a turn, eg. that a frame was
+    /// dropped, without it being part of the value. A message that the next turn doesn't
+    /// take is dropped once the turn after it starts.
+    pub fn release_with<M: Any + Send>(self, message: M) {
+        let baton = &self.sequex.shared.baton;
+        baton.used.store(true, Ordering::SeqCst);
+        baton.state().sent = Some(Box::new(message));
+    }
+
+    /// Take the message the previous turn attached with [Guard::release_with], if it sent
+    /// one of type `M`. A message of another type is left in place.
+    pub fn baton<M: Any>(&mut self) -> Option<M> {
+        let mut state = self.sequex.shared.baton.state();
+        match state.received.take()?.downcast() {
+            Ok(message) => Some(*message),
+            Err(message) => {
+                state.received = Some(message);
+                None
+            }
+        }
+    }
+}
+
+impl Baton {
+    fn state(&self) -> MutexGuard<'_, BatonState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Shared<T> {
+    // Hand the message the previous turn sent to the turn that has just started.
+    pub(crate) fn pass_baton(&self) {
+        if self.baton.used.load(Ordering::SeqCst) {
+            let mut state = self.baton.state();
+            let sent = state.sent.take();
+            let unread = mem::replace(&mut state.received, sent);
+            drop(state);
+            drop(unread);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{is_live, Holder, Sequex, SequexPoisoned, LOCKED};
+use std::{sync::atomic::Ordering, time::Duration};
+
+/// Why a ticket can't acquire the lock right now. Returned by [Sequex::would_block_on].
+#[derive(Clone, Debug)]
+pub struct WouldBlock {
+    serving: u64,
+    held: bool,
+    turns_ahead: u64,
+    waiting_for_frame: bool,
+    holder: Option<Holder>,
+}
+
+impl<T> Sequex<T> {
+    /// Report why this ticket can't acquire the lock right now, without blocking, to explain
+    /// stalls live. Returns `None` if the lock could be acquired, and [SequexPoisoned] if the
+    /// lock was poisoned or the sequence has finished. The holder and how long it has held
+    /// the lock are only known if the sequence tracks its holder, see
+    /// [Builder::track_holder](crate::Builder::track_holder).
+    pub fn would_block_on(&self) -> Result<Option<WouldBlock>, SequexPoisoned> {
+        let shared = &self.shared;
+        let current = shared.current.load(Ordering::SeqCst);
+        if !is_live(current) {
+            return Err(SequexPoisoned);
+        }
+        let held = current == LOCKED;
+        let waiting_for_frame = !shared.frame_open();
+        if current == self.ticket && !waiting_for_frame {
+            return Ok(None);
+        }
+        let serving = if held {
+            shared.serving.load(Ordering::Relaxed)
+        } else {
+            current
+        };
+        let turns_ahead =
+            (self.ticket + self.num_tickets - serving % self.num_tickets) % self.num_tickets;
+        Ok(Some(WouldBlock {
+            serving,
+            held,
+            turns_ahead,
+            waiting_for_frame,
+            holder: held.then(|| shared.holder()).flatten(),
+        }))
+    }
+}
+
+impl WouldBlock {
+    /// Returns the ticket being served, which holds the lock or whose turn it is.
+    pub fn serving(&self) -> u64 {
+        self.serving
+    }
+
+    /// Returns true if the ticket being served holds the lock.
+    pub fn is_held(&self) -> bool {
+        self.held
+    }
+
+    /// Returns the number of turns that come before this ticket's, counting the turn being
+    /// served. Zero if it is this ticket's turn but the sequence is waiting for its frame.
+    pub fn turns_ahead(&self) -> u64 {
+        self.turns_ahead
+    }
+
+    /// Returns true if the sequence is waiting for a [Controller](crate::Controller) to
+    /// advance its frame.
+    pub fn is_waiting_for_frame(&self) -> bool {
+        self.waiting_for_frame
+    }
+
+    /// Returns the ticket and thread holding the lock, if the holder is tracked.
+    pub fn holder(&self) -> Option<&Holder> {
+        self.holder.as_ref()
+    }
+
+    /// Returns how long the lock has been held, if the holder is tracked.
+    pub fn held_for(&self) -> Option<Duration> {
+        self.holder.as_ref().map(Holder::held_for)
+    }
+}
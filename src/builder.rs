@@ -0,0 +1,453 @@
+use crate::{Sequex, Shared, Unpoisoned, Value, WaitStrategy};
+use std::{array, fmt, sync::Arc};
+
+#[cfg(feature = "sync")]
+use crate::{
+    heal::Heal, history::History, journal::Journal, pacing::Pacing, pi, rollback::Rollback,
+    time_slice::TimeSlice, Overrun, OverrunPolicy, PiSequex,
+};
+#[cfg(feature = "sync")]
+use std::{io::Write, time::Duration};
+
+/// A builder that configures a new sequence before creating its tickets. Created with
+/// [Sequex::builder].
+pub struct Builder<T> {
+    value: Value<T>,
+    num_tickets: u64,
+    config: Config,
+    #[cfg(feature = "sync")]
+    pub(crate) rollback: Option<Rollback<T>>,
+    #[cfg(feature = "sync")]
+    pub(crate) heal: Option<Heal<T>>,
+    #[cfg(feature = "sync")]
+    pub(crate) history: Option<History<T>>,
+}
+
+/// An error returned by [Builder::try_build] when a sequence cannot be created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// The sequence was given no tickets.
+    NoTickets,
+    /// The sequence was given more tickets than can be numbered, or indexed on this platform.
+    TooManyTickets,
+    /// The tickets or their shared state could not be allocated.
+    OutOfMemory,
+    /// Two tickets were given the same name with [Builder::ticket].
+    DuplicateName,
+    /// More tickets were named with [Builder::ticket] than the sequence has.
+    TooManyNames,
+}
+
+// Options a sequence was built with.
+pub(crate) struct Config {
+    pub(crate) one_shot: bool,
+    pub(crate) wait_strategy: WaitStrategy,
+    #[cfg(feature = "sync")]
+    pub(crate) priority_inheritance: bool,
+    pub(crate) poisoning: bool,
+    #[cfg(feature = "sync")]
+    pub(crate) frame_gated: bool,
+    #[cfg(feature = "sync")]
+    pub(crate) journal: Option<Journal>,
+    #[cfg(feature = "sync")]
+    pub(crate) track_holder: bool,
+    pub(crate) names: Vec<String>,
+    #[cfg(feature = "sync")]
+    pub(crate) time_slice: Option<TimeSlice>,
+    #[cfg(feature = "sync")]
+    pub(crate) pacing: Option<Pacing>,
+    #[cfg(feature = "sync")]
+    pub(crate) numa_node: Option<u32>,
+    #[cfg(feature = "test-util")]
+    pub(crate) faults: Option<crate::test::FaultInjector>,
+    #[cfg(feature = "trace")]
+    pub(crate) tracer: Option<crate::trace::Tracer>,
+    #[cfg(feature = "metrics")]
+    pub(crate) meter: Option<std::sync::Arc<crate::metrics::Meter>>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            one_shot: false,
+            wait_strategy: WaitStrategy::default(),
+            #[cfg(feature = "sync")]
+            priority_inheritance: false,
+            poisoning: true,
+            #[cfg(feature = "sync")]
+            frame_gated: false,
+            #[cfg(feature = "sync")]
+            journal: None,
+            #[cfg(feature = "sync")]
+            track_holder: false,
+            names: Vec::new(),
+            #[cfg(feature = "sync")]
+            time_slice: None,
+            #[cfg(feature = "sync")]
+            pacing: None,
+            #[cfg(feature = "sync")]
+            numa_node: None,
+            #[cfg(feature = "test-util")]
+            faults: None,
+            #[cfg(feature = "trace")]
+            tracer: None,
+            #[cfg(feature = "metrics")]
+            meter: None,
+        }
+    }
+}
+
+impl Config {
+    // The options a successor of `num_tickets` inherits, without the state kept about the
+    // turns of this sequence. The journal and tracer belong to the sequence they were given
+    // to, and priority inheritance needs guards that stay on their thread, so none of them
+    // carry over.
+    fn renewed(&self, num_tickets: u64) -> Self {
+        Self {
+            one_shot: self.one_shot,
+            wait_strategy: self.wait_strategy,
+            #[cfg(feature = "sync")]
+            priority_inheritance: false,
+            poisoning: self.poisoning,
+            #[cfg(feature = "sync")]
+            frame_gated: self.frame_gated,
+            #[cfg(feature = "sync")]
+            journal: None,
+            #[cfg(feature = "sync")]
+            track_holder: self.track_holder,
+            names: self
+                .names
+                .iter()
+                .take(usize::try_from(num_tickets).unwrap_or(usize::MAX))
+                .cloned()
+                .collect(),
+            #[cfg(feature = "sync")]
+            time_slice: self.time_slice.as_ref().map(TimeSlice::renewed),
+            #[cfg(feature = "sync")]
+            pacing: self.pacing.as_ref().map(Pacing::renewed),
+            #[cfg(feature = "sync")]
+            numa_node: self.numa_node,
+            #[cfg(feature = "test-util")]
+            faults: self.faults.clone(),
+            #[cfg(feature = "trace")]
+            tracer: None,
+            #[cfg(feature = "metrics")]
+            meter: self.meter.clone(),
+        }
+    }
+}
+
+impl<T> Builder<T> {
+    // Start configuring a successor of the sequence of `shared`, of `num_tickets` tickets
+    // over `value`, with the options the sequence was built with.
+    pub(crate) fn successor(shared: &Shared<T>, value: T, num_tickets: u64) -> Self {
+        Builder {
+            value: Value::Ready(value),
+            num_tickets,
+            config: shared.config.renewed(num_tickets),
+            #[cfg(feature = "sync")]
+            rollback: shared.rollback.as_ref().map(Rollback::renewed),
+            #[cfg(feature = "sync")]
+            heal: shared.heal.as_ref().map(Heal::renewed),
+            #[cfg(feature = "sync")]
+            history: shared.history.as_ref().map(History::renewed),
+        }
+    }
+}
+
+impl<T> Sequex<T> {
+    /// Start configuring a new sequence that wraps an internal value.
+    pub fn builder(value: T, num_tickets: u64) -> Builder<T> {
+        Builder {
+            value: Value::Ready(value),
+            num_tickets,
+            config: Config::default(),
+            #[cfg(feature = "sync")]
+            rollback: None,
+            #[cfg(feature = "sync")]
+            heal: None,
+            #[cfg(feature = "sync")]
+            history: None,
+        }
+    }
+
+    /// Start configuring a new sequence whose value is constructed by `init` when the lock is
+    /// first acquired. See [Sequex::new_with].
+    pub fn builder_with(init: impl FnOnce() -> T + Send + 'static, num_tickets: u64) -> Builder<T> {
+        Builder {
+            value: Value::Lazy(Box::new(init)),
+            num_tickets,
+            config: Config::default(),
+            #[cfg(feature = "sync")]
+            rollback: None,
+            #[cfg(feature = "sync")]
+            heal: None,
+            #[cfg(feature = "sync")]
+            history: None,
+        }
+    }
+}
+
+impl<T> Builder<T> {
+    /// Finish the sequence once the last ticket has released its lock, instead of starting
+    /// another cycle at the first ticket. Tickets of a finished sequence can no longer lock it.
+    pub fn one_shot(mut self, one_shot: bool) -> Self {
+        self.config.one_shot = one_shot;
+        self
+    }
+
+    /// Set how [Sequex::lock] waits for a turn. With [WaitStrategy::Spin], locking never
+    /// parks or sleeps the thread. In release builds, taking and releasing turns then also
+    /// takes no locks, makes no system calls and doesn't allocate, apart from constructing a
+    /// lazily initialized value, as long as the sequence uses none of the features that keep
+    /// state about each turn: pacing, time slices, holder tracking, journals, logging,
+    /// tracing, metrics, rollback, history or RCU readers, nor hooks, callbacks, batons,
+    /// mailboxes, schedules, swaps, groups or subscribers. Debug builds also take a lock and
+    /// look up the current thread on every turn, to catch misuse.
+    pub fn wait_strategy(mut self, wait_strategy: WaitStrategy) -> Self {
+        self.config.wait_strategy = wait_strategy;
+        self
+    }
+
+    /// Set whether the sequence is poisoned when a ticket is dropped or [Sequex::lock_with]
+    /// panics, which is the default. Without poisoning, dropped tickets are retired and their
+    /// turns skipped, and panics just pass the turn on. A lazily initialized value that fails
+    /// to construct still poisons the sequence, since there is no value to continue with.
+    pub fn poisoning(mut self, poisoning: bool) -> Self {
+        self.config.poisoning = poisoning;
+        self
+    }
+
+    /// Gate each cycle on an external frame counter, so that the tickets advance at most once
+    /// per frame even if all of them are ready. Cycle `n` only starts once a [Controller] has
+    /// advanced the sequence to frame `n`, and the first cycle runs in frame zero. Only
+    /// available with the `sync` feature.
+    ///
+    /// [Controller]: crate::Controller
+    #[cfg(feature = "sync")]
+    pub fn frame_gated(mut self, frame_gated: bool) -> Self {
+        self.config.frame_gated = frame_gated;
+        self
+    }
+
+    /// Write every turn transition and cycle boundary to an append-only journal, one line per
+    /// record, flushing after each. If the process crashes, [replay](crate::replay) on the
+    /// journal reconstructs where the sequence stopped. Write errors are ignored. Only
+    /// available with the `sync` feature.
+    #[cfg(feature = "sync")]
+    pub fn journal(mut self, writer: impl Write + Send + 'static) -> Self {
+        self.config.journal = Some(Journal::new(writer));
+        self
+    }
+
+    /// Record the ticket and thread that hold the lock, and since when, so that a stalled
+    /// sequence can report which stage and thread it is waiting on. See [Sequex::holder]. Only
+    /// available with the `sync` feature.
+    #[cfg(feature = "sync")]
+    pub fn track_holder(mut self, track_holder: bool) -> Self {
+        self.config.track_holder = track_holder;
+        self
+    }
+
+    /// Name the next unnamed ticket, so that it can be referred to by a name that survives
+    /// reordering the stages. Tickets are named in order, so
+    /// `.ticket("decode").ticket("resize")` names tickets 0 and 1, and tickets that aren't
+    /// named keep only their number. Names appear in [Sequex::dump_state], and in log records
+    /// with the `journal-log` feature. Building fails with [BuildError::DuplicateName] if two
+    /// tickets are given the same name, and with [BuildError::TooManyNames] if more names are
+    /// given than there are tickets.
+    pub fn ticket(mut self, name: impl Into<String>) -> Self {
+        self.config.names.push(name.into());
+        self
+    }
+
+    /// Limit how long a turn may be held. A turn that is held for longer than `max` is
+    /// reported to `on_overrun` once, by a ticket waiting for its turn or by the holder when
+    /// it releases the lock, whichever notices first, and the returned policy is applied. Only
+    /// available with the `sync` feature.
+    #[cfg(feature = "sync")]
+    pub fn time_slice(
+        mut self,
+        max: Duration,
+        on_overrun: impl Fn(&Overrun) -> OverrunPolicy + Send + Sync + 'static,
+    ) -> Self {
+        self.config.time_slice = Some(TimeSlice::new(max, on_overrun));
+        self
+    }
+
+    /// Wait at least `interval` between the starts of two consecutive turns, so that the
+    /// rotation also limits the rate of whatever the turns do. A ticket whose turn comes too
+    /// early waits for the interval to pass before acquiring the lock, and
+    /// [Sequex::try_lock] returns `None` until it has. Only available with the `sync` feature.
+    #[cfg(feature = "sync")]
+    pub fn min_interval(mut self, interval: Duration) -> Self {
+        self.config.pacing = Some(Pacing::new(interval));
+        self
+    }
+
+    /// Place the ticket word of the sequence, which every waiting ticket polls and every
+    /// release writes, in the memory of NUMA node `node`, such as the node of the sockets
+    /// running most of the stages. On Linux, the pages holding it are bound to the node with
+    /// `mbind`, and moved there if the thread building the sequence touched them elsewhere.
+    /// Binding works on whole pages, so the rest of the shared state, and any other heap
+    /// allocations sharing those pages, are bound and moved along with the ticket word. This
+    /// is a hint, which is ignored if the node isn't one the system could have, if the kernel
+    /// rejects it, and on other platforms. Only available with the `sync` feature.
+    #[cfg(feature = "sync")]
+    pub fn numa_node(mut self, node: u32) -> Self {
+        self.config.numa_node = Some(node);
+        self
+    }
+
+    /// Inject the faults of `faults` into the sequence, for testing error handling. Only
+    /// available with the `test-util` feature.
+    #[cfg(feature = "test-util")]
+    pub fn fault_injector(mut self, faults: &crate::test::FaultInjector) -> Self {
+        self.config.faults = Some(faults.clone());
+        self
+    }
+
+    /// Record the turns of the sequence, and the time its tickets spend waiting for them, into
+    /// `trace`, where the sequence is shown under `name`. Only available with the `trace`
+    /// feature.
+    #[cfg(feature = "trace")]
+    pub fn trace(mut self, trace: &crate::Trace, name: impl Into<String>) -> Self {
+        self.config.tracer = Some(trace.tracer(name.into()));
+        self
+    }
+
+    /// Count the turns, cycles and poisoning of the sequence, and the time its tickets spend
+    /// waiting for and holding their turns, in `metrics`, where the sequence is labelled with
+    /// `name`. Sequences given the same name add up to one series. Only available with the
+    /// `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(mut self, metrics: &crate::Metrics, name: impl Into<String>) -> Self {
+        self.config.meter = Some(metrics.meter(name.into()));
+        self
+    }
+
+    /// Create the tickets of the sequence. A sequence with no tickets has no handles, and
+    /// its value is dropped immediately.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [Builder::try_build] fails for any reason other than there being no tickets.
+    #[allow(clippy::panic)]
+    pub fn build(self) -> Vec<Sequex<T>> {
+        match self.try_build() {
+            Ok(tickets) => tickets,
+            Err(BuildError::NoTickets) => Vec::new(),
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Create the tickets of the sequence, returning an error instead of panicking or
+    /// aborting if it has no tickets, too many tickets, or cannot be allocated. Once built,
+    /// acquiring and releasing the lock with [Sequex::try_lock] and [Sequex::lock] never
+    /// panics or allocates, apart from running a lazy initializer and registering wakers,
+    /// callbacks and subscriptions.
+    ///
+    /// The shared state of the sequence is first written by the thread that builds it, so on
+    /// a NUMA system with first-touch placement it lives on that thread's node. Build the
+    /// sequence on a thread pinned to the node of its busiest stages to keep the ticket word
+    /// local to them, or, with the `sync` feature, choose the node of the ticket word with
+    /// `Builder::numa_node`.
+    pub fn try_build(self) -> Result<Vec<Sequex<T>>, BuildError> {
+        if self.num_tickets == 0 {
+            return Err(BuildError::NoTickets);
+        }
+        let num_tickets = self.num_tickets;
+        let shared = self.into_shared()?;
+        let mut tickets = Vec::new();
+        tickets
+            .try_reserve_exact(shared.retired.len())
+            .map_err(|_| BuildError::OutOfMemory)?;
+        tickets.extend((0..num_tickets).map(|ticket| Sequex {
+            ticket,
+            member: 0,
+            num_tickets,
+            read_only: false,
+            shared: shared.clone(),
+        }));
+        Ok(tickets)
+    }
+
+    /// Create the tickets of the sequence as an array, without allocating a list of handles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sequence does not have `N` tickets, or it can't be created for any of the
+    /// reasons [Builder::try_build] reports.
+    #[allow(clippy::panic)]
+    pub fn build_array<const N: usize>(self) -> [Sequex<T>; N] {
+        let num_tickets = self.num_tickets;
+        if num_tickets != N as u64 {
+            panic!("expected {N} tickets, the sequence has {num_tickets}");
+        }
+        let shared = match self.into_shared() {
+            Ok(shared) => shared,
+            Err(error) => panic!("{error}"),
+        };
+        array::from_fn(|ticket| Sequex {
+            ticket: ticket as u64,
+            member: 0,
+            num_tickets,
+            read_only: false,
+            shared: shared.clone(),
+        })
+    }
+
+    /// Create the tickets of a sequence whose lock is backed by a priority-inheriting futex, so
+    /// that a ticket holder is boosted to the priority of the highest priority thread blocked
+    /// waiting for the lock in [PiSequex::lock]. The futex belongs to the thread that locked
+    /// it, so the guards of these tickets can't be sent to other threads. Priority inheritance
+    /// is only supported on Linux, and ignored on other platforms. Only available with the
+    /// `sync` feature.
+    #[cfg(feature = "sync")]
+    pub fn build_priority_inheriting(mut self) -> Vec<PiSequex<T>> {
+        self.config.priority_inheritance = pi::SUPPORTED;
+        self.build().into_iter().map(PiSequex::new).collect()
+    }
+
+    // Create the shared state of the sequence, along with the extensions it was configured
+    // with, and place it in memory.
+    fn into_shared(self) -> Result<Arc<Shared<T>>, BuildError> {
+        #[cfg_attr(not(feature = "sync"), allow(unused_mut))]
+        let mut shared = Shared::new(self.value, self.num_tickets, self.config)?;
+        #[cfg(feature = "sync")]
+        {
+            shared.rollback = self.rollback;
+            shared.heal = self.heal;
+            shared.history = self.history;
+        }
+        let shared = Arc::new(shared);
+        #[cfg(feature = "sync")]
+        shared.place();
+        Ok(shared)
+    }
+
+    /// Create the tickets of a sequence without poisoning, whose guards are returned without a
+    /// `Result`. See [Builder::poisoning].
+    pub fn build_unpoisoned(self) -> Vec<Unpoisoned<T>> {
+        self.poisoning(false)
+            .build()
+            .into_iter()
+            .map(Unpoisoned::new)
+            .collect()
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoTickets => write!(f, "sequence has no tickets"),
+            Self::TooManyTickets => write!(f, "sequence has too many tickets"),
+            Self::OutOfMemory => write!(f, "sequence could not be allocated"),
+            Self::DuplicateName => write!(f, "two tickets have the same name"),
+            Self::TooManyNames => write!(f, "more names than tickets"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
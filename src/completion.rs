@@ -0,0 +1,123 @@
+use crate::{is_live, waiters, Sequex, SequexPoisoned, Shared};
+use std::{
+    sync::{atomic::Ordering, Arc},
+    task::{Poll, Waker},
+};
+
+#[cfg(feature = "async")]
+use std::{future::Future, pin::Pin, task::Context};
+
+/// A handle that resolves once the cycle that was in progress when it was created has
+/// completed, ie. every ticket has taken its turn. Does not own a ticket, so it can be held
+/// by code that orchestrates a sequence without participating in it.
+///
+/// With the `async` feature enabled, the handle can also be awaited.
+pub struct CompletionHandle<T> {
+    shared: Arc<Shared<T>>,
+    cycle: u64,
+}
+
+impl<T> Sequex<T> {
+    /// Create a handle that resolves once the current cycle has completed. If no ticket has
+    /// started the current cycle yet, it resolves after the next full pass.
+    pub fn completion(&self) -> CompletionHandle<T> {
+        CompletionHandle::new(&self.shared)
+    }
+
+    /// Create a handle that resolves once the cycle in progress has completed, after its last
+    /// ticket has released the lock. Unlike [Sequex::completion], it resolves immediately if
+    /// no ticket has started the current cycle yet, which makes it a quiesce point for
+    /// coordinators that need the sequence between cycles, eg. to take a consistent snapshot.
+    pub fn cycle_complete(&self) -> CompletionHandle<T> {
+        CompletionHandle::in_progress(&self.shared)
+    }
+
+    /// Block the current thread until the cycle in progress has completed. See
+    /// [Sequex::cycle_complete]. Returns [SequexPoisoned] if the lock was poisoned before the
+    /// cycle could complete.
+    pub fn wait_cycle_complete(&self) -> Result<(), SequexPoisoned> {
+        self.cycle_complete().wait()
+    }
+}
+
+impl<T> CompletionHandle<T> {
+    pub(crate) fn new(shared: &Arc<Shared<T>>) -> Self {
+        Self {
+            shared: shared.clone(),
+            cycle: shared.cycle.load(Ordering::SeqCst) + 1,
+        }
+    }
+
+    // Create a handle on the cycle in progress, which has already completed if no ticket has
+    // started it.
+    pub(crate) fn in_progress(shared: &Arc<Shared<T>>) -> Self {
+        loop {
+            let cycle = shared.cycle.load(Ordering::SeqCst);
+            let started = shared.cycle_started();
+            if shared.cycle.load(Ordering::SeqCst) == cycle {
+                return Self {
+                    shared: shared.clone(),
+                    cycle: cycle + u64::from(started),
+                };
+            }
+        }
+    }
+
+    /// Returns the cycle this handle is waiting on.
+    pub fn cycle(&self) -> u64 {
+        self.cycle.saturating_sub(1)
+    }
+
+    /// Check whether the cycle has completed, without blocking. Returns [SequexPoisoned] if
+    /// the lock was poisoned before the cycle could complete.
+    pub fn is_complete(&self) -> Result<bool, SequexPoisoned> {
+        match self.check() {
+            Poll::Ready(result) => result.map(|_| true),
+            Poll::Pending => Ok(false),
+        }
+    }
+
+    /// Block the current thread until the cycle has completed. Returns [SequexPoisoned] if
+    /// the lock was poisoned before the cycle could complete.
+    pub fn wait(&self) -> Result<(), SequexPoisoned> {
+        waiters::block_on(|waker| self.poll_complete(waker))
+    }
+
+    fn check(&self) -> Poll<Result<(), SequexPoisoned>> {
+        if self.shared.cycle.load(Ordering::SeqCst) >= self.cycle {
+            return Poll::Ready(Ok(()));
+        }
+        if is_live(self.shared.current.load(Ordering::SeqCst)) {
+            Poll::Pending
+        } else {
+            Poll::Ready(Err(SequexPoisoned))
+        }
+    }
+
+    fn poll_complete(&self, waker: &Waker) -> Poll<Result<(), SequexPoisoned>> {
+        if let Poll::Ready(result) = self.check() {
+            return Poll::Ready(result);
+        }
+        self.shared.cycle_waiters.register(waker);
+        self.check()
+    }
+}
+
+impl<T> Shared<T> {
+    // Returns true if a ticket has taken its turn in the current cycle, or is holding it.
+    fn cycle_started(&self) -> bool {
+        let first = (0..)
+            .zip(self.retired.iter())
+            .find(|(_, retired)| !retired.load(Ordering::SeqCst))
+            .map_or(0, |(ticket, _)| ticket);
+        self.current.load(Ordering::SeqCst) != first
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Future for CompletionHandle<T> {
+    type Output = Result<(), SequexPoisoned>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_complete(cx.waker())
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{is_live, waiters, CompletionHandle, Sequex, SequexPoisoned, Shared, FINISHED};
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    task::Poll,
+};
+
+/// A handle that drives a sequence from outside of it, such as the main loop of a game
+/// advancing the frame that its tickets may run in. Created with [Sequex::controller].
+pub struct Controller<T> {
+    pub(crate) shared: Arc<Shared<T>>,
+}
+
+// Values waiting to be swapped in at the next cycle boundary, and the values they replaced,
+// and whether a swap was ever requested, so that a cycle completes without taking the lock
+// otherwise.
+pub(crate) struct Swaps<T> {
+    used: AtomicBool,
+    state: Mutex<SwapState<T>>,
+}
+
+struct SwapState<T> {
+    next_id: u64,
+    pending: Vec<(u64, T)>,
+    done: Vec<(u64, T)>,
+}
+
+impl<T> Sequex<T> {
+    /// Create a controller of this sequence.
+    pub fn controller(&self) -> Controller<T> {
+        Controller {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Controller<T> {
+    /// Allow cycles up to and including `frame` to run, and wake a ticket that is waiting for
+    /// its cycle to be allowed. The frame never moves backwards, so advancing to an earlier
+    /// frame has no effect. Only sequences built with
+    /// [Builder::frame_gated](crate::Builder::frame_gated) wait for the frame.
+    pub fn advance_to(&self, frame: u64) {
+        self.shared.frame.fetch_max(frame, Ordering::SeqCst);
+        let current = self.shared.current.load(Ordering::SeqCst);
+        if current < FINISHED {
+            self.shared.notify_turn(current);
+        }
+    }
+
+    /// Returns the latest frame the sequence has been advanced to.
+    pub fn frame(&self) -> u64 {
+        self.shared.frame.load(Ordering::SeqCst)
+    }
+
+    /// Returns the cycle the sequence is in, which is the frame that is running or about to
+    /// run.
+    pub fn cycle(&self) -> u64 {
+        self.shared.cycle.load(Ordering::SeqCst)
+    }
+
+    /// Create a handle that resolves once the cycle in progress has completed. See
+    /// [Sequex::cycle_complete].
+    pub fn cycle_complete(&self) -> CompletionHandle<T> {
+        CompletionHandle::in_progress(&self.shared)
+    }
+
+    /// Block the current thread until the cycle in progress has completed. See
+    /// [Sequex::cycle_complete]. Returns [SequexPoisoned] if the lock was poisoned before the
+    /// cycle could complete.
+    pub fn wait_cycle_complete(&self) -> Result<(), SequexPoisoned> {
+        self.cycle_complete().wait()
+    }
+
+    /// Replace the value with `value` at the next cycle boundary, after the last ticket of
+    /// the current cycle releases the lock and before the first ticket of the next one takes
+    /// its turn, and return the value it replaced. Blocks the current thread until the
+    /// boundary is reached. Swaps made at the same boundary are applied in the order they were
+    /// requested. Returns [SequexPoisoned] if the sequence ended before the boundary.
+    pub fn swap_at_cycle_boundary(&self, value: T) -> Result<T, SequexPoisoned> {
+        let id = {
+            let mut swaps = self.shared.swaps.state();
+            let id = swaps.next_id;
+            swaps.next_id += 1;
+            swaps.pending.push((id, value));
+            self.shared.swaps.used.store(true, Ordering::SeqCst);
+            id
+        };
+        waiters::block_on(|waker| {
+            if let Poll::Ready(result) = self.check_swap(id) {
+                return Poll::Ready(result);
+            }
+            self.shared.cycle_waiters.register(waker);
+            self.check_swap(id)
+        })
+    }
+
+    fn check_swap(&self, id: u64) -> Poll<Result<T, SequexPoisoned>> {
+        let mut swaps = self.shared.swaps.state();
+        if let Some(index) = swaps.done.iter().position(|(done, _)| *done == id) {
+            return Poll::Ready(Ok(swaps.done.swap_remove(index).1));
+        }
+        if is_live(self.shared.current.load(Ordering::SeqCst)) {
+            return Poll::Pending;
+        }
+        swaps.pending.retain(|(pending, _)| *pending != id);
+        Poll::Ready(Err(SequexPoisoned))
+    }
+}
+
+impl<T> Swaps<T> {
+    fn state(&self) -> MutexGuard<'_, SwapState<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Default for Swaps<T> {
+    fn default() -> Self {
+        Self {
+            used: AtomicBool::new(false),
+            state: Mutex::new(SwapState {
+                next_id: 0,
+                pending: Vec::new(),
+                done: Vec::new(),
+            }),
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    // Swap in the values requested by controllers as a cycle completes. Must only be called
+    // while holding the lock.
+    pub(crate) fn apply_swaps(&self) {
+        if !self.swaps.used.load(Ordering::SeqCst) {
+            return;
+        }
+        let mut swaps = self.swaps.state();
+        if swaps.pending.is_empty() {
+            return;
+        }
+        unsafe { self.initialize() };
+        if !self.initialized.load(Ordering::SeqCst) {
+            return;
+        }
+        let value = unsafe { (*self.value.get()).assume_init_mut() };
+        let pending = mem::take(&mut swaps.pending);
+        for (id, replacement) in pending {
+            let replaced = mem::replace(value, replacement);
+            swaps.done.push((id, replaced));
+        }
+        drop(swaps);
+        self.bump_version();
+    }
+}
+
+impl<T> Clone for Controller<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{Guard, Sequex, SequexPoisoned};
+use std::{
+    ops::{Deref, DerefMut},
+    thread,
+};
+
+/// A turn that reads the value in place and only clones it on the first mutable access. The
+/// copy is written back when the guard is dropped, unless the thread is panicking or the
+/// changes were discarded with [CowGuard::discard]. A turn that only reads never clones the
+/// value and leaves its version untouched. Created with [Sequex::lock_cow] or
+/// [Guard::into_cow].
+pub struct CowGuard<'a, T: Clone> {
+    guard: Guard<'a, T>,
+    copy: Option<T>,
+}
+
+impl<T: Clone> Sequex<T> {
+    /// Take this ticket's turn, blocking until it comes, with a guard that clones the value
+    /// on the first mutable access. Returns [SequexPoisoned] if the lock was poisoned or the
+    /// sequence has finished.
+    pub fn lock_cow(&self) -> Result<CowGuard<'_, T>, SequexPoisoned> {
+        self.lock().map(Guard::into_cow)
+    }
+}
+
+impl<'a, T: Clone> Guard<'a, T> {
+    /// Hold on to this turn with a guard that clones the value on the first mutable access.
+    pub fn into_cow(self) -> CowGuard<'a, T> {
+        CowGuard {
+            guard: self,
+            copy: None,
+        }
+    }
+}
+
+impl<T: Clone> CowGuard<'_, T> {
+    /// Returns the value as it was when the turn started.
+    pub fn original(&self) -> &T {
+        &self.guard
+    }
+
+    /// Returns true if the value was cloned for a mutable access.
+    pub fn is_modified(&self) -> bool {
+        self.copy.is_some()
+    }
+
+    /// Discard any changes and release the turn, leaving the value untouched.
+    pub fn discard(mut self) {
+        self.copy = None;
+    }
+}
+
+impl<T: Clone> Deref for CowGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.copy.as_ref().unwrap_or(&self.guard)
+    }
+}
+
+impl<T: Clone> DerefMut for CowGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let guard = &self.guard;
+        self.copy.get_or_insert_with(|| T::clone(guard))
+    }
+}
+
+impl<T: Clone> Drop for CowGuard<'_, T> {
+    fn drop(&mut self) {
+        // A panic leaves the value as it was at the start of the turn.
+        if let Some(copy) = self.copy.take() {
+            if !thread::panicking() {
+                *self.guard = copy;
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{Guard, Shared};
+use std::{
+    collections::VecDeque,
+    sync::{atomic::Ordering, Mutex, MutexGuard, PoisonError},
+};
+
+// Tickets that yielded their turn, to be served again at the end of the current cycle. Only
+// the holder of the lock touches them.
+#[derive(Default)]
+pub(crate) struct Deferred {
+    state: Mutex<DeferredState>,
+}
+
+#[derive(Default)]
+struct DeferredState {
+    queue: VecDeque<u64>,
+    serving: bool,
+}
+
+impl<T> Guard<'_, T> {
+    /// Give up the rest of this turn and re-queue this ticket at the end of the current cycle,
+    /// after the last ticket, so a stage that is waiting on slow external data doesn't hold up
+    /// the tickets behind it. Lock the ticket again to take the re-queued turn. The cycle
+    /// only completes once every re-queued turn has been taken.
+    ///
+    /// If no other turn is left in the cycle, such as for the last ticket when no turns were
+    /// re-queued before it, the re-queued turn would be served right away, so the turn is
+    /// kept and its guard returned instead.
+    pub fn yield_turn(self) -> Option<Self> {
+        let shared = &self.sequex.shared;
+        let mut deferred = shared.deferred.state();
+        if shared.yields_to_nobody(&deferred, self.sequex.ticket, self.sequex.num_tickets) {
+            drop(deferred);
+            return Some(self);
+        }
+        deferred.queue.push_back(self.sequex.ticket);
+        shared.deferring.store(true, Ordering::SeqCst);
+        drop(deferred);
+        None
+    }
+}
+
+impl Deferred {
+    fn state(&self) -> MutexGuard<'_, DeferredState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Shared<T> {
+    // Returns the tickets whose re-queued turns are still to be served, in order.
+    pub(crate) fn deferred_tickets(&self) -> Vec<u64> {
+        self.deferred.state().queue.iter().copied().collect()
+    }
+
+    // Returns true if no turn other than `ticket`'s is left in the cycle, so that it would be
+    // served again right after yielding.
+    fn yields_to_nobody(&self, deferred: &DeferredState, ticket: u64, num_tickets: u64) -> bool {
+        if !deferred.queue.is_empty() {
+            return false;
+        }
+        deferred.serving
+            || (ticket + 1..num_tickets).all(|next| {
+                self.skips_retired()
+                    && self
+                        .retired
+                        .get(next as usize)
+                        .is_some_and(|retired| retired.load(Ordering::SeqCst))
+            })
+    }
+
+    // Returns the ticket whose turn follows `ticket`'s, and whether the order wrapped around
+    // to start a new cycle, serving re-queued turns before the cycle wraps.
+    pub(crate) fn next_turn(&self, ticket: u64, num_tickets: u64) -> (u64, bool) {
+        if !self.deferring.load(Ordering::SeqCst) {
+            return self.next_ticket(ticket, num_tickets);
+        }
+        let mut deferred = self.deferred.state();
+        let (next, wrapped) = if deferred.serving {
+            self.next_ticket(num_tickets - 1, num_tickets)
+        } else {
+            self.next_ticket(ticket, num_tickets)
+        };
+        if !wrapped {
+            return (next, false);
+        }
+        while let Some(yielded) = deferred.queue.pop_front() {
+            let retired = self
+                .retired
+                .get(yielded as usize)
+                .is_some_and(|retired| retired.load(Ordering::SeqCst));
+            if !self.skips_retired() || !retired {
+                deferred.serving = true;
+                return (yielded, false);
+            }
+        }
+        deferred.serving = false;
+        self.deferring.store(false, Ordering::SeqCst);
+        (next, true)
+    }
+}
@@ -0,0 +1,231 @@
+use crate::{try_boxed_slice, BuildError, Sequex, FINISHED, LOCKED, MOVED, POISON};
+use std::{
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// A description of the state of a sequence for debugging, such as when attaching to a
+/// process that is stuck, which can be written as JSON or as a DOT graph of the turn order.
+/// Taken with [Sequex::dump_state]. The state is read without stopping the sequence, so it
+/// may be inconsistent if tickets take turns while it is taken.
+#[derive(Clone, Debug)]
+pub struct StateDump {
+    num_tickets: u64,
+    live: u64,
+    retired: Vec<u64>,
+    names: Vec<String>,
+    state: &'static str,
+    turn: Option<u64>,
+    cycle: u64,
+    frame: Option<u64>,
+    version: u64,
+    holder: Option<(u64, String, f64)>,
+    waiting: Vec<(u64, u64, usize)>,
+    deferred: Vec<u64>,
+}
+
+// The number of threads blocked waiting for each ticket's turn.
+pub(crate) struct Waiting {
+    threads: Box<[AtomicU64]>,
+}
+
+// Counts a thread as waiting until it is dropped.
+pub(crate) struct WaitingGuard<'a> {
+    count: Option<&'a AtomicU64>,
+}
+
+impl<T> Sequex<T> {
+    /// Describe the state of the sequence: its tickets, their names and which of them are
+    /// retired, whose turn it is and who holds it, the cycle, and the threads and tasks
+    /// waiting on each ticket. The holding thread is only known if the sequence was built with
+    /// [Builder::track_holder](crate::Builder::track_holder).
+    pub fn dump_state(&self) -> StateDump {
+        let shared = &self.shared;
+        let current = shared.current.load(Ordering::SeqCst);
+        let (state, turn) = match current {
+            LOCKED => ("locked", Some(shared.serving.load(Ordering::Relaxed))),
+            POISON => ("poisoned", None),
+            MOVED => ("moved", None),
+            FINISHED => ("finished", None),
+            current => ("free", Some(current)),
+        };
+        #[cfg(feature = "sync")]
+        let frame = shared.frame.load(Ordering::SeqCst);
+        #[cfg(not(feature = "sync"))]
+        let frame = u64::MAX;
+        StateDump {
+            num_tickets: shared.num_tickets,
+            live: shared.ticket_count(),
+            retired: shared.retired_tickets(),
+            names: shared.config.names.clone(),
+            state,
+            turn,
+            cycle: shared.cycle.load(Ordering::SeqCst),
+            frame: (frame != u64::MAX).then_some(frame),
+            version: shared.version.load(Ordering::SeqCst),
+            #[cfg(feature = "sync")]
+            holder: shared.holder().map(|holder| {
+                let name = holder
+                    .thread_name()
+                    .map_or_else(|| format!("{:?}", holder.thread_id()), String::from);
+                (holder.ticket(), name, holder.held_for().as_secs_f64())
+            }),
+            #[cfg(not(feature = "sync"))]
+            holder: None,
+            waiting: (0..)
+                .zip(
+                    shared
+                        .turn_waiters
+                        .iter()
+                        .zip(shared.waiting.threads.iter()),
+                )
+                .map(|(ticket, (tasks, threads))| {
+                    (ticket, threads.load(Ordering::SeqCst), tasks.len())
+                })
+                .filter(|(_, threads, tasks)| *threads > 0 || *tasks > 0)
+                .collect(),
+            deferred: shared.deferred_tickets(),
+        }
+    }
+}
+
+impl StateDump {
+    /// Write the state as a JSON object.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        let _ = write!(
+            json,
+            "{{\"tickets\":{},\"live\":{},\"retired\":{:?},\"names\":[",
+            self.num_tickets, self.live, self.retired,
+        );
+        for (i, name) in self.names.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            let _ = write!(json, "{separator}\"{}\"", Escaped(name));
+        }
+        let _ = write!(
+            json,
+            "],\"state\":\"{}\",\"turn\":{},\"cycle\":{},\"frame\":{},\"version\":{},\
+             \"holder\":",
+            self.state,
+            Json(self.turn),
+            self.cycle,
+            Json(self.frame),
+            self.version,
+        );
+        let _ = match &self.holder {
+            Some((ticket, thread, held_for)) => write!(
+                json,
+                "{{\"ticket\":{ticket},\"thread\":\"{}\",\"held_for\":{held_for}}}",
+                Escaped(thread)
+            ),
+            None => write!(json, "null"),
+        };
+        json.push_str(",\"waiting\":[");
+        for (i, (ticket, threads, tasks)) in self.waiting.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            let _ = write!(
+                json,
+                "{separator}{{\"ticket\":{ticket},\"threads\":{threads},\"tasks\":{tasks}}}"
+            );
+        }
+        let _ = write!(json, "],\"deferred\":{:?}}}", self.deferred);
+        json
+    }
+
+    /// Write the turn order as a DOT graph, with a node per ticket, labelled with its name if
+    /// it has one, and an edge to the ticket whose turn follows it. The ticket whose turn it
+    /// is is filled, a held turn is drawn in bold, and retired tickets are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph sequex {\n    rankdir=LR;\n");
+        let _ = writeln!(dot, "    label=\"{} at cycle {}\";", self.state, self.cycle);
+        for ticket in 0..self.num_tickets {
+            let mut style = Vec::new();
+            if self.turn == Some(ticket) {
+                style.push("filled");
+                if self.state == "locked" {
+                    style.push("bold");
+                }
+            }
+            if self.retired.contains(&ticket) {
+                style.push("dashed");
+            }
+            let waiting = self
+                .waiting
+                .iter()
+                .find(|(waiting, _, _)| *waiting == ticket)
+                .map_or(0, |(_, threads, tasks)| *threads + *tasks as u64);
+            let label = match self.names.get(ticket as usize) {
+                Some(name) => format!("{ticket}: {}", Escaped(name)),
+                None => ticket.to_string(),
+            };
+            let _ = writeln!(
+                dot,
+                "    t{ticket} [label=\"{label}\\nwaiting: {waiting}\", style=\"{}\"];",
+                style.join(",")
+            );
+        }
+        for ticket in 1..self.num_tickets {
+            let _ = writeln!(dot, "    t{} -> t{ticket};", ticket - 1);
+        }
+        let _ = writeln!(
+            dot,
+            "    t{} -> t0 [style=dotted, label=\"next cycle\"];\n}}",
+            self.num_tickets.saturating_sub(1)
+        );
+        dot
+    }
+}
+
+// Escapes a string for a JSON string literal.
+pub(crate) struct Escaped<'a>(pub(crate) &'a str);
+
+impl std::fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Writes an optional number as a JSON value.
+struct Json(Option<u64>);
+
+impl std::fmt::Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{value}"),
+            None => f.write_str("null"),
+        }
+    }
+}
+
+impl Waiting {
+    pub(crate) fn new(len: usize) -> Result<Self, BuildError> {
+        Ok(Self {
+            threads: try_boxed_slice(len, || AtomicU64::new(0))?,
+        })
+    }
+
+    // Count the current thread as waiting for `ticket`'s turn.
+    pub(crate) fn enter(&self, ticket: u64) -> WaitingGuard<'_> {
+        let count = self.threads.get(ticket as usize);
+        if let Some(count) = count {
+            count.fetch_add(1, Ordering::Relaxed);
+        }
+        WaitingGuard { count }
+    }
+}
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(count) = self.count {
+            count.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{group::Groups, Guard, Sequex, SequexPoisoned};
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+/// One of several handles to a single position in the turn order, for passing the position
+/// between components, eg. when handing work over to another worker. Created with
+/// [Sequex::duplicate]. The turns of the position are taken by whichever handle locks
+/// first, as for [Sequex::group_member], but locking the position while another of its
+/// handles is locking it or holds its turn is an error, instead of waiting for a turn that a
+/// thread holding both would never get.
+pub struct Duplicate<T> {
+    sequex: Sequex<T>,
+}
+
+/// An RAII guard of a [Duplicate], which releases the lock and the position's claim when
+/// dropped.
+pub struct DuplicateGuard<'a, T> {
+    // Dropped before the claim, so that the turn is released before another handle can lock.
+    guard: Guard<'a, T>,
+    _claim: Claim<'a>,
+}
+
+// A duplicate handle's claim on its position, given up when dropped.
+struct Claim<'a> {
+    groups: &'a Groups,
+    ticket: u64,
+}
+
+/// The error returned when locking a [Duplicate] fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DuplicateError {
+    /// Another handle to the position is locking it or holds its turn.
+    Held,
+    /// The lock was poisoned or the sequence has finished.
+    Poisoned,
+}
+
+impl<T> Sequex<T> {
+    /// Turn this ticket into two handles that share its position in the turn order. See
+    /// [Duplicate].
+    pub fn duplicate(self) -> [Duplicate<T>; 2] {
+        let other = self.group_member();
+        [Duplicate { sequex: self }, Duplicate { sequex: other }]
+    }
+}
+
+impl<T> Duplicate<T> {
+    /// Create another handle to this position.
+    pub fn duplicate(&self) -> Self {
+        Self {
+            sequex: self.sequex.group_member(),
+        }
+    }
+
+    /// Returns the position these handles share in the sequence.
+    pub fn ticket(&self) -> u64 {
+        self.sequex.ticket()
+    }
+
+    /// Attempt to acquire the lock, without blocking the current thread. Returns
+    /// [DuplicateError::Held] if another handle to the position is locking it or holds its
+    /// turn.
+    pub fn try_lock(&self) -> Result<Option<DuplicateGuard<'_, T>>, DuplicateError> {
+        let claim = self.claim()?;
+        Ok(self.sequex.try_lock()?.map(|guard| DuplicateGuard {
+            guard,
+            _claim: claim,
+        }))
+    }
+
+    /// Acquire the lock, blocking the current thread until it is the position's turn.
+    /// Returns [DuplicateError::Held] if another handle to the position is locking it or
+    /// holds its turn.
+    pub fn lock(&self) -> Result<DuplicateGuard<'_, T>, DuplicateError> {
+        let claim = self.claim()?;
+        Ok(DuplicateGuard {
+            guard: self.sequex.lock()?,
+            _claim: claim,
+        })
+    }
+
+    /// Returns the underlying handle, as a member of the position's group. See
+    /// [Sequex::group_member].
+    pub fn into_inner(self) -> Sequex<T> {
+        self.sequex
+    }
+
+    // Claim the position for as long as locking blocks and the turn it acquires is held, so
+    // that no other handle to the position can wait on it in the meantime.
+    fn claim(&self) -> Result<Claim<'_>, DuplicateError> {
+        let groups = &self.sequex.shared.groups;
+        if !groups.claim(self.sequex.ticket) {
+            return Err(DuplicateError::Held);
+        }
+        Ok(Claim {
+            groups,
+            ticket: self.sequex.ticket,
+        })
+    }
+}
+
+impl<T> DuplicateGuard<'_, T> {
+    /// Returns the cycle this turn belongs to. See [Guard::cycle].
+    pub fn cycle(&self) -> u64 {
+        self.guard.cycle()
+    }
+}
+
+impl<T> Deref for DuplicateGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for DuplicateGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl Drop for Claim<'_> {
+    fn drop(&mut self) {
+        self.groups.unclaim(self.ticket);
+    }
+}
+
+impl From<SequexPoisoned> for DuplicateError {
+    fn from(_: SequexPoisoned) -> Self {
+        Self::Poisoned
+    }
+}
+
+impl fmt::Display for DuplicateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Held => write!(f, "position is held by another handle"),
+            Self::Poisoned => write!(f, "sequex poisoned"),
+        }
+    }
+}
+
+impl std::error::Error for DuplicateError {}
@@ -0,0 +1,43 @@
+use crate::{Sequex, SequexPoisoned, Status};
+
+/// The operations of a ticket that don't depend on the type of its value, so that tickets of
+/// sequences protecting different types can be held together as `Box<dyn DynTicket>`, for
+/// example by an orchestrator that monitors and shuts down a set of pipelines.
+pub trait DynTicket: Send + Sync {
+    /// Returns the position of this ticket in its sequence.
+    fn ticket(&self) -> u64;
+
+    /// Block until it is this ticket's turn. See [Sequex::wait_for_turn].
+    fn wait_for_turn(&self) -> Result<(), SequexPoisoned>;
+
+    /// Wait for this ticket's turn and pass it on. See [Sequex::pass].
+    fn pass(&self) -> Result<(), SequexPoisoned>;
+
+    /// Returns the state of the sequence as seen by this ticket. See [Sequex::status].
+    fn status(&self) -> Status;
+
+    /// Poison the sequence to shut it down. See [Sequex::poison].
+    fn poison(&self);
+}
+
+impl<T: Send> DynTicket for Sequex<T> {
+    fn ticket(&self) -> u64 {
+        Sequex::ticket(self)
+    }
+
+    fn wait_for_turn(&self) -> Result<(), SequexPoisoned> {
+        Sequex::wait_for_turn(self)
+    }
+
+    fn pass(&self) -> Result<(), SequexPoisoned> {
+        Sequex::pass(self)
+    }
+
+    fn status(&self) -> Status {
+        Sequex::status(self)
+    }
+
+    fn poison(&self) {
+        Sequex::poison(self)
+    }
+}
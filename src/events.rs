@@ -0,0 +1,120 @@
+use crate::Sequex;
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    mpsc::Sender,
+    Arc, Mutex, PoisonError,
+};
+
+/// A transition of a sequence that callbacks registered with [Sequex::on_event] are
+/// notified of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Event {
+    /// The cycle with the given number completed, ie. its last ticket released the lock.
+    CycleComplete(u64),
+    /// The value was replaced instead of the lock being poisoned. See
+    /// [Builder::heal_with](crate::Builder::heal_with).
+    Healed,
+    /// The lock was poisoned.
+    Poisoned,
+    /// The sequence finished and will not hand out any more turns.
+    Finished,
+}
+
+type Callback = Arc<dyn Fn(Event) + Send + Sync>;
+
+// Callbacks registered on a sequence, and whether any ever were, so that sequences without
+// callbacks take no lock to notify them.
+#[derive(Default)]
+pub(crate) struct Callbacks {
+    used: AtomicBool,
+    callbacks: Mutex<Vec<Callback>>,
+}
+
+impl Callbacks {
+    pub(crate) fn register(&self, callback: Callback) {
+        self.callbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(callback);
+        self.used.store(true, Ordering::SeqCst);
+    }
+
+    // Notify every callback of an event. The callbacks run outside of the registry lock so
+    // that they can register further callbacks.
+    pub(crate) fn notify(&self, event: Event) {
+        if !self.used.load(Ordering::SeqCst) {
+            return;
+        }
+        let callbacks = self
+            .callbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        for callback in callbacks {
+            callback(event);
+        }
+    }
+}
+
+// Notifies a subscriber, returning false once it is no longer listening.
+type Subscriber = Box<dyn Fn() -> bool + Send>;
+
+// Subscribers to the turns of individual tickets.
+#[derive(Default)]
+pub(crate) struct TurnSubscribers {
+    count: AtomicUsize,
+    subscribers: Mutex<Vec<(u64, Subscriber)>>,
+}
+
+impl TurnSubscribers {
+    fn subscribe(&self, ticket: u64, subscriber: Subscriber) {
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        subscribers.push((ticket, subscriber));
+        self.count.store(subscribers.len(), Ordering::SeqCst);
+    }
+
+    // Notify the subscribers of a ticket, or of every ticket if `ticket` is `None`, dropping
+    // any that stopped listening.
+    pub(crate) fn notify(&self, ticket: Option<u64>) {
+        if self.count.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        subscribers.retain(|(subscribed, notify)| {
+            ticket.is_some_and(|ticket| ticket != *subscribed) || notify()
+        });
+        self.count.store(subscribers.len(), Ordering::SeqCst);
+    }
+}
+
+impl<T> Sequex<T> {
+    /// Send `message` on `sender` whenever this ticket's turn becomes current, so that event
+    /// driven threads can wait for their turn alongside other messages instead of blocking in
+    /// [Sequex::lock]. If it is already this ticket's turn the message is sent immediately.
+    /// The message is also sent once the sequence is poisoned or finishes, after which locking
+    /// returns an error. The subscription ends when the receiver is dropped.
+    pub fn subscribe<M: Clone + Send + 'static>(&self, sender: Sender<M>, message: M) {
+        let notify = move || sender.send(message.clone()).is_ok();
+        if self.shared.current.load(Ordering::SeqCst) == self.ticket && !notify() {
+            return;
+        }
+        self.shared
+            .turn_subscribers
+            .subscribe(self.ticket, Box::new(notify));
+    }
+
+    /// Register a callback that is invoked whenever the sequence completes a cycle, is
+    /// poisoned or finishes, so supervision logic can react without polling. Callbacks run
+    /// on the thread that caused the transition, usually while it releases a lock, and
+    /// should return quickly and not panic.
+    pub fn on_event(&self, callback: impl Fn(Event) + Send + Sync + 'static) {
+        self.shared.callbacks.register(Arc::new(callback));
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{journal::Record, Event, Sequex, LOCKED, MOVED, POISON};
+use std::sync::{atomic::Ordering, Mutex, MutexGuard, PoisonError};
+
+/// A plain lock over the value of a sequence whose other tickets have all been dropped,
+/// created with [Sequex::into_exclusive]. Locks are acquired on demand, in no particular
+/// order, so that tearing down a pipeline doesn't have to go through the ticket protocol.
+pub struct Exclusive<T> {
+    value: Mutex<T>,
+    was_poisoned: bool,
+}
+
+impl<T> Sequex<T> {
+    /// Take the value out of the sequence once this is the last ticket alive. Succeeds even if
+    /// the sequence was poisoned, since dropping the other tickets poisons it, which is
+    /// reported by [Exclusive::was_poisoned]. Returns the ticket back if other tickets or
+    /// members of its group are alive, an observer is holding the lock, or the value was
+    /// already moved out or failed to initialize. Observers of the sequence see it as ended.
+    pub fn into_exclusive(self) -> Result<Exclusive<T>, Self> {
+        let shared = &self.shared;
+        let current = shared.current.load(Ordering::SeqCst);
+        if shared.ticket_count() != 1
+            || self.group_size() != 1
+            || current == LOCKED
+            || current == MOVED
+        {
+            return Err(self);
+        }
+        if shared
+            .current
+            .compare_exchange(current, LOCKED, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(self);
+        }
+        shared.annotate_acquire();
+        unsafe { shared.initialize() };
+        if !shared.initialized.load(Ordering::SeqCst) {
+            shared.current.store(current, Ordering::SeqCst);
+            return Err(self);
+        }
+        let value = unsafe { (*shared.value.get()).assume_init_read() };
+        shared.current.store(MOVED, Ordering::SeqCst);
+        shared.record(Record::Moved);
+        shared.notify_ended();
+        shared.callbacks.notify(Event::Finished);
+        Ok(Exclusive {
+            value: Mutex::new(value),
+            was_poisoned: current == POISON,
+        })
+    }
+}
+
+impl<T> Exclusive<T> {
+    /// Lock the value, blocking the current thread while another guard is alive.
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.value.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns a mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the value.
+    pub fn into_inner(self) -> T {
+        self.value
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns true if the sequence had been poisoned when the value was taken out of it.
+    pub fn was_poisoned(&self) -> bool {
+        self.was_poisoned
+    }
+}
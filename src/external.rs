@@ -0,0 +1,127 @@
+use crate::{Guard, Sequex, SequexPoisoned};
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, MutexGuard, RwLock, RwLockWriteGuard},
+};
+
+/// A lock owned by other code, which a [Sequenced] ticket acquires once it is its turn.
+/// Implemented for [Mutex] and [RwLock], and can be implemented for other mutexes, such as
+/// the ones built with `lock_api`.
+pub trait ExternalLock {
+    /// The value protected by the lock.
+    type Target: ?Sized;
+
+    /// The guard that holds the lock.
+    type Guard<'a>: DerefMut<Target = Self::Target>
+    where
+        Self: 'a;
+
+    /// Acquire the lock, blocking the current thread until it is available. Returns
+    /// [SequexPoisoned] if the lock is poisoned.
+    fn lock_external(&self) -> Result<Self::Guard<'_>, SequexPoisoned>;
+}
+
+/// A ticket that imposes a turn order on a value that lives in a lock shared with other code,
+/// without moving the value into a [Sequex]. Other code can still acquire the lock outside
+/// of the order, but the tickets only ever acquire it in turn.
+pub struct Sequenced<L> {
+    sequex: Sequex<()>,
+    lock: Arc<L>,
+}
+
+/// An RAII guard of a [Sequenced] ticket, which holds both its turn and the external lock,
+/// and releases the lock before passing on the turn when dropped.
+pub struct SequencedGuard<'a, L: ExternalLock + 'a> {
+    value: L::Guard<'a>,
+    turn: Guard<'a, ()>,
+}
+
+impl<L: ExternalLock> Sequenced<L> {
+    /// Create a sequence of tickets over a shared lock.
+    pub fn new(lock: Arc<L>, num_tickets: u64) -> Vec<Self> {
+        Sequex::new((), num_tickets)
+            .into_iter()
+            .map(|sequex| Self {
+                sequex,
+                lock: lock.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the position of this ticket in the sequence.
+    pub fn ticket(&self) -> u64 {
+        self.sequex.ticket()
+    }
+
+    /// Attempt to take this ticket's turn without blocking, then acquire the external lock,
+    /// which blocks if other code holds it. Returns [SequexPoisoned] if the sequence or the
+    /// external lock was poisoned.
+    pub fn try_lock(&self) -> Result<Option<SequencedGuard<'_, L>>, SequexPoisoned> {
+        let Some(turn) = self.sequex.try_lock()? else {
+            return Ok(None);
+        };
+        Ok(Some(SequencedGuard {
+            value: self.lock.lock_external()?,
+            turn,
+        }))
+    }
+
+    /// Take this ticket's turn, blocking until it comes, then acquire the external lock.
+    /// Returns [SequexPoisoned] if the sequence or the external lock was poisoned.
+    pub fn lock(&self) -> Result<SequencedGuard<'_, L>, SequexPoisoned> {
+        let turn = self.sequex.lock()?;
+        Ok(SequencedGuard {
+            value: self.lock.lock_external()?,
+            turn,
+        })
+    }
+
+    /// Returns the shared lock.
+    pub fn inner(&self) -> &Arc<L> {
+        &self.lock
+    }
+}
+
+impl<'a, L: ExternalLock + 'a> SequencedGuard<'a, L> {
+    /// Returns the cycle this turn belongs to. See [Guard::cycle].
+    pub fn cycle(&self) -> u64 {
+        self.turn.cycle()
+    }
+}
+
+impl<'a, L: ExternalLock + 'a> Deref for SequencedGuard<'a, L> {
+    type Target = L::Target;
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<'a, L: ExternalLock + 'a> DerefMut for SequencedGuard<'a, L> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T: ?Sized> ExternalLock for Mutex<T> {
+    type Target = T;
+    type Guard<'a>
+        = MutexGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock_external(&self) -> Result<MutexGuard<'_, T>, SequexPoisoned> {
+        self.lock().map_err(|_| SequexPoisoned)
+    }
+}
+
+impl<T: ?Sized> ExternalLock for RwLock<T> {
+    type Target = T;
+    type Guard<'a>
+        = RwLockWriteGuard<'a, T>
+    where
+        T: 'a;
+
+    fn lock_external(&self) -> Result<RwLockWriteGuard<'_, T>, SequexPoisoned> {
+        self.write().map_err(|_| SequexPoisoned)
+    }
+}
@@ -0,0 +1,234 @@
+//! A C API for taking part in a sequence from foreign code. Enabled with the `ffi` feature.
+//!
+//! Tickets are passed to C as opaque [SequexTicket] handles, either created from an existing
+//! [Sequex] with [into_ffi], or as a new sequence over a C pointer with [sequex_create]. A
+//! locked turn is represented by an opaque [SequexGuard] handle, which must be released with
+//! [sequex_unlock] before its ticket is freed. Locking does not allocate. A C header is
+//! provided at `include/sequex.h`.
+
+use crate::{BuildError, Guard, PoisonCause, PoisonReason, Sequex, SequexPoisoned};
+use std::{
+    ffi::c_void,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+/// The operation succeeded.
+pub const SEQUEX_OK: i32 = 0;
+/// The lock could not be acquired without blocking.
+pub const SEQUEX_WOULD_BLOCK: i32 = 1;
+/// The lock was poisoned, or the sequence has finished.
+pub const SEQUEX_POISONED: i32 = -1;
+/// A required pointer argument was null.
+pub const SEQUEX_INVALID: i32 = -2;
+/// The sequence was given more tickets than can be numbered, or indexed on this platform.
+pub const SEQUEX_TOO_MANY_TICKETS: i32 = -3;
+/// The sequence could not be allocated.
+pub const SEQUEX_OUT_OF_MEMORY: i32 = -4;
+/// The call panicked, such as on a misuse caught in debug builds. The panic is caught so
+/// that it doesn't unwind into foreign code, which would abort the process.
+pub const SEQUEX_PANICKED: i32 = -5;
+
+/// An opaque handle to a ticket of a sequence.
+pub struct SequexTicket {
+    ticket: Box<dyn RawTicket>,
+    data: *mut c_void,
+}
+
+/// An opaque handle to a locked turn. Guard handles point into their ticket, so they are
+/// only valid while the ticket is alive.
+pub struct SequexGuard {
+    _private: [u8; 0],
+}
+
+// A ticket with its value type erased. Guards are forgotten when locked, and reconstructed to
+// release the lock.
+trait RawTicket {
+    fn try_lock(&self) -> Result<bool, SequexPoisoned>;
+    fn lock(&self) -> Result<(), SequexPoisoned>;
+    unsafe fn unlock(&self);
+    fn poison(&self);
+}
+
+impl<T> RawTicket for Sequex<T> {
+    fn try_lock(&self) -> Result<bool, SequexPoisoned> {
+        Ok(Sequex::try_lock(self)?.map(mem::forget).is_some())
+    }
+
+    fn lock(&self) -> Result<(), SequexPoisoned> {
+        Sequex::lock(self).map(mem::forget)
+    }
+
+    unsafe fn unlock(&self) {
+        drop(Guard {
+            sequex: self,
+            mutated: true,
+        });
+    }
+
+    fn poison(&self) {
+        self.shared
+            .poison(PoisonCause::new(Some(self.ticket), PoisonReason::Requested));
+    }
+}
+
+/// Convert a ticket into a handle that can be passed to C. The guard data pointer of the
+/// handle points to the protected value. Free the handle with [sequex_ticket_free].
+pub fn into_ffi<T: Send + 'static>(sequex: Sequex<T>) -> *mut SequexTicket {
+    let data = sequex.data_ptr().cast();
+    Box::into_raw(Box::new(SequexTicket {
+        ticket: Box::new(sequex),
+        data,
+    }))
+}
+
+/// Create a new sequence of `num_tickets` tickets over a C pointer, writing the ticket handles
+/// to `tickets`. The guard data pointer of each ticket is `data` itself. A sequence with no
+/// tickets writes no handles. Returns [SEQUEX_INVALID] if `tickets` is null,
+/// [SEQUEX_TOO_MANY_TICKETS] if the sequence has more tickets than can be indexed, or
+/// [SEQUEX_OUT_OF_MEMORY] if it could not be allocated, in which case no handles are written.
+///
+/// # Safety
+///
+/// `tickets` must be valid for writing `num_tickets` handles, and `data` must be safe to
+/// access from whichever thread holds the lock.
+#[no_mangle]
+pub unsafe extern "C" fn sequex_create(
+    data: *mut c_void,
+    num_tickets: u64,
+    tickets: *mut *mut SequexTicket,
+) -> i32 {
+    if tickets.is_null() {
+        return SEQUEX_INVALID;
+    }
+    catch_panic(|| {
+        let sequexes = match Sequex::builder((), num_tickets).try_build() {
+            Ok(sequexes) => sequexes,
+            Err(BuildError::NoTickets) => return SEQUEX_OK,
+            Err(BuildError::OutOfMemory) => return SEQUEX_OUT_OF_MEMORY,
+            Err(_) => return SEQUEX_TOO_MANY_TICKETS,
+        };
+        let tickets = slice::from_raw_parts_mut(tickets, sequexes.len());
+        for (handle, sequex) in tickets.iter_mut().zip(sequexes) {
+            *handle = Box::into_raw(Box::new(SequexTicket {
+                ticket: Box::new(sequex),
+                data,
+            }));
+        }
+        SEQUEX_OK
+    })
+}
+
+/// Free a ticket handle. Like dropping a [Sequex], this poisons the sequence.
+///
+/// # Safety
+///
+/// `ticket` must be null or a handle that was not freed yet, and none of its guards may still
+/// be held.
+#[no_mangle]
+pub unsafe extern "C" fn sequex_ticket_free(ticket: *mut SequexTicket) {
+    if !ticket.is_null() {
+        catch_panic(|| {
+            drop(Box::from_raw(ticket));
+            SEQUEX_OK
+        });
+    }
+}
+
+/// Acquire the lock, blocking the current thread until it is this ticket's turn, and write the
+/// guard handle to `guard`. Returns [SEQUEX_POISONED] if the lock was poisoned.
+///
+/// # Safety
+///
+/// `ticket` must be a live ticket handle, and `guard` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sequex_lock(
+    ticket: *const SequexTicket,
+    guard: *mut *mut SequexGuard,
+) -> i32 {
+    if ticket.is_null() || guard.is_null() {
+        return SEQUEX_INVALID;
+    }
+    catch_panic(|| match (*ticket).ticket.lock() {
+        Ok(()) => {
+            *guard = ticket.cast_mut().cast();
+            SEQUEX_OK
+        }
+        Err(SequexPoisoned) => SEQUEX_POISONED,
+    })
+}
+
+/// Attempt to acquire the lock without blocking, writing the guard handle to `guard` on
+/// success. Returns [SEQUEX_WOULD_BLOCK] if it is not this ticket's turn yet, or
+/// [SEQUEX_POISONED] if the lock was poisoned.
+///
+/// # Safety
+///
+/// `ticket` must be a live ticket handle, and `guard` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn sequex_try_lock(
+    ticket: *const SequexTicket,
+    guard: *mut *mut SequexGuard,
+) -> i32 {
+    if ticket.is_null() || guard.is_null() {
+        return SEQUEX_INVALID;
+    }
+    catch_panic(|| match (*ticket).ticket.try_lock() {
+        Ok(true) => {
+            *guard = ticket.cast_mut().cast();
+            SEQUEX_OK
+        }
+        Ok(false) => SEQUEX_WOULD_BLOCK,
+        Err(SequexPoisoned) => SEQUEX_POISONED,
+    })
+}
+
+/// Release a lock, passing the turn to the next ticket.
+///
+/// # Safety
+///
+/// `guard` must be null or a guard handle that was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn sequex_unlock(guard: *mut SequexGuard) {
+    if let Some(ticket) = guard.cast::<SequexTicket>().as_ref() {
+        catch_panic(|| {
+            ticket.ticket.unlock();
+            SEQUEX_OK
+        });
+    }
+}
+
+/// Returns the data pointer of a locked turn, or null if `guard` is null.
+///
+/// # Safety
+///
+/// `guard` must be null or a guard handle that was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn sequex_guard_data(guard: *const SequexGuard) -> *mut c_void {
+    match guard.cast::<SequexTicket>().as_ref() {
+        Some(ticket) => ticket.data,
+        None => ptr::null_mut(),
+    }
+}
+
+/// Poison the sequence, waking every ticket with [SEQUEX_POISONED].
+///
+/// # Safety
+///
+/// `ticket` must be null or a live ticket handle.
+#[no_mangle]
+pub unsafe extern "C" fn sequex_poison(ticket: *const SequexTicket) {
+    if let Some(ticket) = ticket.as_ref() {
+        catch_panic(|| {
+            ticket.ticket.poison();
+            SEQUEX_OK
+        });
+    }
+}
+
+// Run the body of a C function, returning [SEQUEX_PANICKED] instead of unwinding into the
+// caller if it panics.
+fn catch_panic(f: impl FnOnce() -> i32) -> i32 {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(SEQUEX_PANICKED)
+}
@@ -0,0 +1,146 @@
+use crate::{try_boxed_slice, BuildError, Sequex, Shared};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, Mutex, MutexGuard, PoisonError,
+};
+
+// The number of handles to each ticket's position in the turn order, how many of them must
+// take each of its turns, and whether a duplicate handle has claimed each position.
+pub(crate) struct Groups {
+    members: Box<[AtomicU64]>,
+    quorums: Box<[AtomicU64]>,
+    next_member: AtomicU64,
+    claimed: Box<[AtomicBool]>,
+    // The members that took the turn being served, while it waits for its quorum.
+    confirmed: Mutex<Vec<u64>>,
+}
+
+impl<T> Sequex<T> {
+    /// Create another handle to this ticket's position in the turn order, joining a group of
+    /// handles that take the position's turns between them. Whichever member locks first
+    /// serves the turn, and the others wait for the position's next turn, so redundant
+    /// workers can share a position for fault tolerance without fighting over distinct
+    /// tickets. The position is only retired, or the sequence poisoned, once every member of
+    /// the group has been dropped.
+    pub fn group_member(&self) -> Sequex<T> {
+        if let Some(members) = self.shared.groups.members.get(self.ticket as usize) {
+            members.fetch_add(1, Ordering::SeqCst);
+        }
+        Sequex {
+            ticket: self.ticket,
+            member: self
+                .shared
+                .groups
+                .next_member
+                .fetch_add(1, Ordering::SeqCst)
+                + 1,
+            num_tickets: self.num_tickets,
+            read_only: false,
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Returns the number of handles to this ticket's position, including this one. See
+    /// [Sequex::group_member].
+    pub fn group_size(&self) -> u64 {
+        self.shared
+            .groups
+            .members
+            .get(self.ticket as usize)
+            .map_or(1, |members| members.load(Ordering::SeqCst))
+    }
+
+    /// Require `quorum` distinct members of this ticket's group to each lock and release the
+    /// position's turns before the turn passes on. Until then, the turn is handed back to
+    /// the position after each release, and members that already took it wait for the next
+    /// turn. If fewer than `quorum` members are left, the position's turns never complete.
+    /// A quorum of zero or one lets a single member serve the turn, as by default.
+    pub fn set_quorum(&self, quorum: u64) {
+        if let Some(quorums) = self.shared.groups.quorums.get(self.ticket as usize) {
+            quorums.store(quorum, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns the number of members of this ticket's group that must take each of its
+    /// turns. See [Sequex::set_quorum].
+    pub fn quorum(&self) -> u64 {
+        self.shared.groups.quorum(self.ticket)
+    }
+}
+
+impl Groups {
+    pub(crate) fn new(len: usize) -> Result<Self, BuildError> {
+        Ok(Self {
+            members: try_boxed_slice(len, || AtomicU64::new(1))?,
+            quorums: try_boxed_slice(len, || AtomicU64::new(1))?,
+            next_member: AtomicU64::new(0),
+            claimed: try_boxed_slice(len, || AtomicBool::new(false))?,
+            confirmed: Mutex::default(),
+        })
+    }
+
+    // Claim the position of `ticket` for a duplicate handle about to lock it. Returns false
+    // if another handle has claimed it.
+    pub(crate) fn claim(&self, ticket: u64) -> bool {
+        self.claimed.get(ticket as usize).is_some_and(|claimed| {
+            claimed
+                .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+        })
+    }
+
+    // Give up the claim of a duplicate handle on the position of `ticket`.
+    pub(crate) fn unclaim(&self, ticket: u64) {
+        if let Some(claimed) = self.claimed.get(ticket as usize) {
+            claimed.store(false, Ordering::SeqCst);
+        }
+    }
+
+    fn quorum(&self, ticket: u64) -> u64 {
+        self.quorums
+            .get(ticket as usize)
+            .map_or(1, |quorum| quorum.load(Ordering::SeqCst).max(1))
+    }
+
+    fn confirmed(&self) -> MutexGuard<'_, Vec<u64>> {
+        self.confirmed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Shared<T> {
+    // Remove a handle from `ticket`'s group. Returns true if other members remain, so the
+    // position carries on.
+    pub(crate) fn leave_group(&self, ticket: u64) -> bool {
+        self.groups
+            .members
+            .get(ticket as usize)
+            .is_some_and(|members| members.fetch_sub(1, Ordering::SeqCst) > 1)
+    }
+
+    // Returns true if `member` already took the turn of `ticket` being served, which is
+    // waiting for the rest of its quorum.
+    pub(crate) fn quorum_blocks(&self, ticket: u64, member: u64) -> bool {
+        self.groups.quorum(ticket) > 1 && self.groups.confirmed().contains(&member)
+    }
+
+    // Count `member` towards the quorum of the turn of `ticket` it is releasing, unless the
+    // turn panicked. Returns true if the turn is complete and can pass on.
+    pub(crate) fn reach_quorum(&self, ticket: u64, member: u64, panicked: bool) -> bool {
+        let quorum = self.groups.quorum(ticket);
+        if quorum <= 1 {
+            return true;
+        }
+        if panicked {
+            return false;
+        }
+        let mut confirmed = self.groups.confirmed();
+        confirmed.push(member);
+        if (confirmed.len() as u64) < quorum {
+            return false;
+        }
+        confirmed.clear();
+        true
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{Builder, Event, Shared};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+// Makes a fresh value to replace one left behind by a failure that would have poisoned the
+// sequence, and whether the replacement is still to be made by the next holder.
+pub(crate) struct Heal<T> {
+    make: Arc<dyn Fn() -> T + Send + Sync>,
+    pending: AtomicBool,
+}
+
+impl<T> Builder<T> {
+    /// Instead of poisoning the sequence when a holder panics in
+    /// [Sequex::lock_with](crate::Sequex::lock_with), or a ticket is dropped, replace the
+    /// value with one made by `make` and carry on from the next ticket, for services that
+    /// prefer degraded but alive over dead. A dropped ticket is retired and skipped from then
+    /// on, and the value is replaced by the next ticket to take its turn. Callbacks registered
+    /// with [Sequex::on_event](crate::Sequex::on_event) are notified with [Event::Healed].
+    /// Poisoning the sequence explicitly, or with a time slice, still ends it.
+    pub fn heal_with(mut self, make: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        self.heal = Some(Heal {
+            make: Arc::new(make),
+            pending: AtomicBool::new(false),
+        });
+        self
+    }
+}
+
+impl<T> Heal<T> {
+    // The same way of healing, for a successor of the sequence.
+    pub(crate) fn renewed(&self) -> Self {
+        Self {
+            make: self.make.clone(),
+            pending: AtomicBool::new(false),
+        }
+    }
+}
+
+impl<T: Default + 'static> Builder<T> {
+    /// Replace the value with its default instead of poisoning the sequence. See
+    /// [Builder::heal_with].
+    pub fn heal_with_default(self) -> Self {
+        self.heal_with(T::default)
+    }
+}
+
+impl<T> Shared<T> {
+    pub(crate) fn heals(&self) -> bool {
+        self.heal.is_some()
+    }
+
+    // Replace the value as the holder of the lock fails. Must only be called while holding
+    // the lock, once the value has been initialized.
+    pub(crate) unsafe fn heal_now(&self) {
+        if let Some(heal) = &self.heal {
+            heal.pending.store(false, Ordering::SeqCst);
+            *(*self.value.get()).assume_init_mut() = (heal.make)();
+            self.bump_version();
+            self.callbacks.notify(Event::Healed);
+        }
+    }
+
+    // Have the next holder of the lock replace the value, as a ticket failed without it.
+    pub(crate) fn heal_later(&self) {
+        if let Some(heal) = &self.heal {
+            heal.pending.store(true, Ordering::SeqCst);
+        }
+    }
+
+    // Make a replacement that is due, as a turn starts. Must only be called while holding
+    // the lock, once the value has been initialized.
+    pub(crate) unsafe fn heal_pending(&self) {
+        if let Some(heal) = &self.heal {
+            if heal.pending.load(Ordering::SeqCst) {
+                self.heal_now();
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{Builder, Sequex, Shared};
+use std::{
+    collections::VecDeque,
+    sync::{atomic::Ordering, Mutex, MutexGuard, PoisonError},
+};
+
+// The value as of the latest cycle boundaries, oldest first, tagged with the cycle that
+// ended at each, and how to clone the next one.
+pub(crate) struct History<T> {
+    snapshots: Mutex<VecDeque<(u64, T)>>,
+    capacity: usize,
+    clone: fn(&T) -> T,
+}
+
+impl<T: Clone> Builder<T> {
+    /// Keep a clone of the value as of each of the last `cycles` cycle boundaries, taken as
+    /// the last turn of a cycle is released, so that the state can be inspected a few
+    /// iterations back when debugging, with [Sequex::history_at]. Older snapshots are dropped
+    /// as new ones are taken. Keeping no cycles, which is the default, clones nothing.
+    pub fn history(mut self, cycles: usize) -> Self {
+        self.history = (cycles > 0).then(|| History {
+            snapshots: Mutex::new(VecDeque::new()),
+            capacity: cycles,
+            clone: T::clone,
+        });
+        self
+    }
+}
+
+impl<T: Clone> Sequex<T> {
+    /// Returns a clone of the value as it was left at the end of `cycle`, if it is still in
+    /// the history kept with [Builder::history]. The value two cycles before the one in
+    /// progress is at `cycle - 2`.
+    pub fn history_at(&self, cycle: u64) -> Option<T> {
+        let history = self.shared.history.as_ref()?;
+        history
+            .snapshots()
+            .iter()
+            .find(|(ended, _)| *ended == cycle)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Returns clones of every value in the history kept with [Builder::history], oldest
+    /// first, along with the cycle each was left at the end of.
+    pub fn history(&self) -> Vec<(u64, T)> {
+        self.shared
+            .history
+            .as_ref()
+            .map_or_else(Vec::new, |history| {
+                history.snapshots().iter().cloned().collect()
+            })
+    }
+}
+
+impl<T> History<T> {
+    // An empty history of the same length, for a successor of the sequence.
+    pub(crate) fn renewed(&self) -> Self {
+        Self {
+            snapshots: Mutex::new(VecDeque::new()),
+            capacity: self.capacity,
+            clone: self.clone,
+        }
+    }
+
+    fn snapshots(&self) -> MutexGuard<'_, VecDeque<(u64, T)>> {
+        self.snapshots
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Shared<T> {
+    // Add the value to the history as the current cycle completes. Must only be called while
+    // holding the lock.
+    pub(crate) fn save_history(&self) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        if !self.initialized.load(Ordering::SeqCst) {
+            return;
+        }
+        let cycle = self.cycle.load(Ordering::SeqCst);
+        let value = unsafe { (history.clone)((*self.value.get()).assume_init_ref()) };
+        let mut snapshots = history.snapshots();
+        if snapshots.len() == history.capacity {
+            snapshots.pop_front();
+        }
+        snapshots.push_back((cycle, value));
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{Sequex, Shared};
+use std::{
+    sync::{Mutex, MutexGuard, PoisonError},
+    thread::{self, Thread, ThreadId},
+    time::{Duration, Instant},
+};
+
+/// The ticket and thread holding the lock of a sequence, recorded when the sequence is built
+/// with [Builder::track_holder](crate::Builder::track_holder).
+#[derive(Clone, Debug)]
+pub struct Holder {
+    ticket: u64,
+    thread: Thread,
+    since: Instant,
+}
+
+// The holder of a sequence's lock, if it is tracked.
+#[derive(Default)]
+pub(crate) struct HolderSlot {
+    holder: Mutex<Option<Holder>>,
+}
+
+impl<T> Sequex<T> {
+    /// Returns the ticket and thread that hold the lock, if the sequence tracks its holder
+    /// and the lock is held by a ticket.
+    pub fn holder(&self) -> Option<Holder> {
+        self.shared.holder()
+    }
+}
+
+impl<T> Shared<T> {
+    pub(crate) fn holder(&self) -> Option<Holder> {
+        self.holder.get().clone()
+    }
+
+    // Record that `ticket` has acquired the lock on the current thread, or that the lock was
+    // released, along with the holder if it is tracked.
+    pub(crate) fn set_holder(&self, ticket: Option<u64>) {
+        self.set_locked_by(ticket);
+        if self.config.track_holder {
+            *self.holder.get() = ticket.map(|ticket| Holder {
+                ticket,
+                thread: thread::current(),
+                since: Instant::now(),
+            });
+        }
+    }
+}
+
+impl HolderSlot {
+    fn get(&self) -> MutexGuard<'_, Option<Holder>> {
+        self.holder.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Holder {
+    /// Returns the ticket that holds the lock.
+    pub fn ticket(&self) -> u64 {
+        self.ticket
+    }
+
+    /// Returns the id of the thread that acquired the lock.
+    pub fn thread_id(&self) -> ThreadId {
+        self.thread.id()
+    }
+
+    /// Returns the name of the thread that acquired the lock, if it has one.
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread.name()
+    }
+
+    /// Returns how long the lock has been held since it was acquired.
+    pub fn held_for(&self) -> Duration {
+        self.since.elapsed()
+    }
+}
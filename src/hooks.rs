@@ -0,0 +1,169 @@
+use crate::{PoisonCause, PoisonReason, Sequex, SequexPoisoned, Shared};
+use std::{
+    ops::Deref,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+};
+
+// A hook invoked with a turn and the value it holds.
+type Hook<T> = Arc<dyn Fn(&mut HookTurn<'_, T>) + Send + Sync>;
+
+// Hooks registered on a sequence to run as turns start and end. Nothing is run until a hook
+// is first registered.
+pub(crate) struct Hooks<T> {
+    used: AtomicBool,
+    after_acquire: Mutex<Vec<Hook<T>>>,
+    before_release: Mutex<Vec<Hook<T>>>,
+}
+
+/// A turn seen by a hook registered with [Sequex::after_acquire] or [Sequex::before_release],
+/// which derefs to the value it holds. The value is only considered modified by the hook,
+/// bumping its [version](Sequex::version), if it is borrowed with [HookTurn::get_mut].
+pub struct HookTurn<'a, T> {
+    ticket: u64,
+    cycle: u64,
+    value: &'a mut T,
+    read_only: bool,
+    mutated: bool,
+}
+
+impl<T> HookTurn<'_, T> {
+    /// Returns the ticket whose turn this is.
+    pub fn ticket(&self) -> u64 {
+        self.ticket
+    }
+
+    /// Returns the cycle this turn belongs to. See [Guard::cycle](crate::Guard::cycle).
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+    /// Returns mutable access to the value, which marks it as modified, or `None` during
+    /// the turns of a [ReadOnly](crate::ReadOnly) ticket, which must leave the value as it
+    /// found it.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if self.read_only {
+            return None;
+        }
+        self.mutated = true;
+        Some(self.value)
+    }
+
+    /// Returns true if this is the turn of a [ReadOnly](crate::ReadOnly) ticket, whose value
+    /// can't be borrowed mutably.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+}
+
+impl<T> Deref for HookTurn<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> Sequex<T> {
+    /// Register a hook that is invoked with every turn right after it is acquired, for
+    /// cross-cutting concerns such as validation or logging without wrapping every call site.
+    /// Hooks run on the thread that acquired the turn, in the order they were registered. If
+    /// a hook panics, the panic is caught and the sequence is poisoned, whether or not it was
+    /// built with poisoning, and the turn is returned as [SequexPoisoned].
+    pub fn after_acquire(&self, hook: impl Fn(&mut HookTurn<'_, T>) + Send + Sync + 'static) {
+        self.shared
+            .hooks
+            .register(&self.shared.hooks.after_acquire, hook);
+    }
+
+    /// Register a hook that is invoked with every turn just before it is released, eg. to
+    /// invalidate a cache. It isn't invoked for a turn whose holder is panicking. If a hook
+    /// panics, the panic is caught and the sequence is poisoned, and the turn is still
+    /// released. See [Sequex::after_acquire].
+    pub fn before_release(&self, hook: impl Fn(&mut HookTurn<'_, T>) + Send + Sync + 'static) {
+        self.shared
+            .hooks
+            .register(&self.shared.hooks.before_release, hook);
+    }
+}
+
+impl<T> Hooks<T> {
+    fn register(
+        &self,
+        hooks: &Mutex<Vec<Hook<T>>>,
+        hook: impl Fn(&mut HookTurn<'_, T>) + Send + Sync + 'static,
+    ) {
+        hooks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Arc::new(hook));
+        self.used.store(true, Ordering::SeqCst);
+    }
+}
+
+impl<T> Default for Hooks<T> {
+    fn default() -> Self {
+        Self {
+            used: AtomicBool::new(false),
+            after_acquire: Mutex::default(),
+            before_release: Mutex::default(),
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    // Run the hooks of the turn `ticket` has just acquired, which may only read the value if
+    // the turn is read-only. Returns true if any of them modified the value, or poisons the
+    // sequence if one panicked. Must only be called while holding the lock, once the value
+    // has been initialized.
+    pub(crate) unsafe fn run_acquire_hooks(
+        &self,
+        ticket: u64,
+        read_only: bool,
+    ) -> Result<bool, SequexPoisoned> {
+        self.run_hooks(&self.hooks.after_acquire, ticket, read_only)
+    }
+
+    // Run the hooks of the turn `ticket` is about to release. See `run_acquire_hooks`.
+    pub(crate) unsafe fn run_release_hooks(
+        &self,
+        ticket: u64,
+        read_only: bool,
+    ) -> Result<bool, SequexPoisoned> {
+        self.run_hooks(&self.hooks.before_release, ticket, read_only)
+    }
+
+    // The hooks run outside of the registry lock so that they can register further hooks.
+    unsafe fn run_hooks(
+        &self,
+        hooks: &Mutex<Vec<Hook<T>>>,
+        ticket: u64,
+        read_only: bool,
+    ) -> Result<bool, SequexPoisoned> {
+        if !self.hooks.used.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+        let hooks = hooks.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        let mut turn = HookTurn {
+            ticket,
+            cycle: self.cycle.load(Ordering::SeqCst),
+            value: (*self.value.get()).assume_init_mut(),
+            read_only,
+            mutated: false,
+        };
+        let ran = panic::catch_unwind(AssertUnwindSafe(|| {
+            for hook in &hooks {
+                hook(&mut turn);
+            }
+        }));
+        match ran {
+            Ok(()) => Ok(turn.mutated),
+            Err(_) => {
+                self.poison(PoisonCause::new(Some(ticket), PoisonReason::Panicked));
+                Err(SequexPoisoned)
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::Shared;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    OnceLock,
+};
+
+/// A sequence that async tasks can wait on, as reported to an [Instrument]. Only available
+/// with the `async` feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Resource {
+    /// Identifies the sequence in the calls made to the [Instrument]. Never reused.
+    pub id: u64,
+    /// The number of tickets the sequence was created with.
+    pub num_tickets: u64,
+}
+
+/// An async task waiting for a ticket's turn, as reported to an [Instrument]. Only
+/// available with the `async` feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Wait<'a> {
+    /// The [Resource::id] of the sequence waited on.
+    pub resource: u64,
+    /// The ticket whose turn the task is waiting for.
+    pub ticket: u64,
+    /// The name of the ticket, if it was given one with
+    /// [Builder::ticket](crate::Builder::ticket).
+    pub name: Option<&'a str>,
+    /// The method the task is waiting in, such as `"Sequex::lock_async"`.
+    pub source: &'static str,
+}
+
+/// Receives the sequences created and the waits of async tasks on their turns, once
+/// installed with [set_instrument], so that they can be shown as named resources in a task
+/// inspector. Waits made with [Sequex::lock_async](crate::Sequex::lock_async),
+/// [Sequex::lock_and](crate::Sequex::lock_and) and
+/// [Sequex::lock_async_timeout](crate::Sequex::lock_async_timeout) are reported, from their
+/// first poll until the future completes or is dropped.
+///
+/// To show them in tokio-console, implement it with the `tracing` crate in a build with
+/// `tokio_unstable`: open a span with target `"runtime::resource"`, `concrete_type =
+/// "Sequex"` and `kind = "Sync"` for each resource, keyed by its id, and for each wait a span
+/// with target `"runtime::resource::async_op"` and the source, inside the span of its
+/// resource, entering it for each poll and recording the ticket and readiness with
+/// `"runtime::resource::poll_op"` events. Only available with the `async` feature.
+pub trait Instrument: Send + Sync {
+    /// A sequence was created.
+    fn resource_created(&self, resource: Resource);
+
+    /// The sequence with this [Resource::id] was dropped, along with its last ticket.
+    fn resource_dropped(&self, id: u64);
+
+    /// A task started waiting for a turn.
+    fn wait_started(&self, wait: Wait<'_>);
+
+    /// A task waiting for a turn was polled, which returned ready if `ready`.
+    fn wait_polled(&self, wait: Wait<'_>, ready: bool);
+
+    /// A task stopped waiting for a turn, because it took it, the sequence ended, or the
+    /// future was dropped.
+    fn wait_ended(&self, wait: Wait<'_>);
+}
+
+static INSTRUMENT: OnceLock<Box<dyn Instrument>> = OnceLock::new();
+
+static NEXT_RESOURCE: AtomicU64 = AtomicU64::new(0);
+
+/// Install the instrument that receives the resources and waits of every sequence. Returns
+/// the instrument back if one was already installed. Sequences created before it was
+/// installed are not reported. Only available with the `async` feature.
+pub fn set_instrument(instrument: impl Instrument + 'static) -> Result<(), Box<dyn Instrument>> {
+    INSTRUMENT.set(Box::new(instrument))
+}
+
+// Returns the id of a new sequence of `num_tickets`, reporting it to the instrument.
+pub(crate) fn create_resource(num_tickets: u64) -> u64 {
+    let id = NEXT_RESOURCE.fetch_add(1, Ordering::Relaxed);
+    if let Some(instrument) = INSTRUMENT.get() {
+        instrument.resource_created(Resource { id, num_tickets });
+    }
+    id
+}
+
+// Report that the sequence with this id was dropped.
+pub(crate) fn drop_resource(id: u64) {
+    if let Some(instrument) = INSTRUMENT.get() {
+        instrument.resource_dropped(id);
+    }
+}
+
+// A wait on a turn that has been reported to the instrument, which ends when it is dropped.
+pub(crate) struct InstrumentedWait<'a, T> {
+    instrument: &'static dyn Instrument,
+    shared: &'a Shared<T>,
+    ticket: u64,
+    source: &'static str,
+}
+
+impl<T> Shared<T> {
+    // Report that a task started waiting for the turn of `ticket` in `source`, if an
+    // instrument is installed.
+    pub(crate) fn instrument_wait(
+        &self,
+        ticket: u64,
+        source: &'static str,
+    ) -> Option<InstrumentedWait<'_, T>> {
+        let wait = InstrumentedWait {
+            instrument: &**INSTRUMENT.get()?,
+            shared: self,
+            ticket,
+            source,
+        };
+        wait.instrument.wait_started(wait.wait());
+        Some(wait)
+    }
+}
+
+impl<T> InstrumentedWait<'_, T> {
+    // Report that the task was polled.
+    pub(crate) fn polled(&self, ready: bool) {
+        self.instrument.wait_polled(self.wait(), ready);
+    }
+
+    fn wait(&self) -> Wait<'_> {
+        Wait {
+            resource: self.shared.resource,
+            ticket: self.ticket,
+            name: self.shared.ticket_name(self.ticket),
+            source: self.source,
+        }
+    }
+}
+
+impl<T> Drop for InstrumentedWait<'_, T> {
+    fn drop(&mut self) {
+        self.instrument.wait_ended(self.wait());
+    }
+}
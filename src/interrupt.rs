@@ -0,0 +1,48 @@
+use crate::{Guard, Sequex, SequexPoisoned};
+use std::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// The error returned by [Sequex::lock_interruptible].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InterruptError {
+    /// The interrupt flag was raised before the turn came.
+    Interrupted,
+    /// The lock was poisoned or the sequence has finished.
+    Poisoned,
+}
+
+impl<T> Sequex<T> {
+    /// Acquire the lock like [Sequex::lock], but give up with [InterruptError::Interrupted]
+    /// once `interrupted` is set, so that eg. Ctrl-C can unwind a blocked pipeline stage.
+    /// Register the flag with a signal handler, such as `signal_hook::flag::register`, or set
+    /// it from another thread. The flag is checked before every attempt, and at least every
+    /// 10ms while the thread is parked, sleeps until a scheduled or paced turn may start, or
+    /// blocks on the holder of a priority-inheriting turn. It is left set for the caller to
+    /// clear.
+    pub fn lock_interruptible(
+        &self,
+        interrupted: &AtomicBool,
+    ) -> Result<Guard<'_, T>, InterruptError> {
+        self.lock_unless(|| interrupted.load(Ordering::SeqCst))
+    }
+}
+
+impl From<SequexPoisoned> for InterruptError {
+    fn from(_: SequexPoisoned) -> Self {
+        Self::Poisoned
+    }
+}
+
+impl fmt::Display for InterruptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Interrupted => write!(f, "interrupted waiting for the turn"),
+            Self::Poisoned => write!(f, "sequex poisoned"),
+        }
+    }
+}
+
+impl std::error::Error for InterruptError {}
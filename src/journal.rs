@@ -0,0 +1,152 @@
+use std::fmt;
+
+#[cfg(feature = "sync")]
+use std::{
+    io::{self, BufRead, Write},
+    sync::{Mutex, PoisonError},
+};
+
+// An append-only log of the transitions of a sequence, written one line per record and
+// flushed before the transition takes effect where possible.
+#[cfg(feature = "sync")]
+pub(crate) struct Journal {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+// A transition written to a journal.
+pub(crate) enum Record {
+    Acquire { ticket: u64, cycle: u64 },
+    Release { ticket: u64, cycle: u64 },
+    Return { ticket: u64 },
+    Cycle(u64),
+    Poisoned,
+    Finished,
+    Moved,
+}
+
+/// The state of a sequence reconstructed from its journal with [replay], for example to find
+/// out which stage of a pipeline a crashed process died in. See
+/// [Builder::journal](crate::Builder::journal).
+#[cfg(feature = "sync")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Replay {
+    cycle: u64,
+    holder: Option<u64>,
+    last_released: Option<u64>,
+    poisoned: bool,
+    finished: bool,
+    moved: bool,
+}
+
+#[cfg(feature = "sync")]
+impl Journal {
+    pub(crate) fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+        }
+    }
+
+    // Write a record. Errors are ignored, since there is nobody to report them to while the
+    // lock changes hands.
+    pub(crate) fn record(&self, record: Record) {
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let _ = writeln!(writer, "{record}").and_then(|_| writer.flush());
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Acquire { ticket, cycle } => write!(f, "acquire {ticket} {cycle}"),
+            Self::Release { ticket, cycle } => write!(f, "release {ticket} {cycle}"),
+            Self::Return { ticket } => write!(f, "return {ticket}"),
+            Self::Cycle(cycle) => write!(f, "cycle {cycle}"),
+            Self::Poisoned => write!(f, "poisoned"),
+            Self::Finished => write!(f, "finished"),
+            Self::Moved => write!(f, "moved"),
+        }
+    }
+}
+
+/// Reconstruct the state of a sequence from the journal it wrote. A final record that was only
+/// partially written, because the process died while writing it, is ignored. Returns an
+/// [io::ErrorKind::InvalidData] error if the journal contains a malformed record.
+#[cfg(feature = "sync")]
+pub fn replay(mut reader: impl BufRead) -> io::Result<Replay> {
+    let mut replay = Replay::default();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || !line.ends_with('\n') {
+            return Ok(replay);
+        }
+        replay.apply(line.trim_end())?;
+    }
+}
+
+#[cfg(feature = "sync")]
+impl Replay {
+    fn apply(&mut self, line: &str) -> io::Result<()> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed journal record");
+        let mut words = line.split(' ');
+        let kind = words.next().ok_or_else(invalid)?;
+        let mut number = || -> io::Result<u64> {
+            words
+                .next()
+                .and_then(|word| word.parse().ok())
+                .ok_or_else(invalid)
+        };
+        match kind {
+            "acquire" => {
+                self.holder = Some(number()?);
+                self.cycle = number()?;
+            }
+            "release" => {
+                self.last_released = Some(number()?);
+                self.cycle = number()?;
+                self.holder = None;
+            }
+            "return" => {
+                number()?;
+                self.holder = None;
+            }
+            "cycle" => self.cycle = number()?,
+            "poisoned" => self.poisoned = true,
+            "finished" => self.finished = true,
+            "moved" => self.moved = true,
+            _ => return Err(invalid()),
+        }
+        Ok(())
+    }
+
+    /// Returns the cycle the sequence was in when the journal ended.
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+    /// Returns the ticket that held the lock when the journal ended, ie. the stage that was
+    /// running.
+    pub fn holder(&self) -> Option<u64> {
+        self.holder
+    }
+
+    /// Returns the ticket that most recently released the lock.
+    pub fn last_released(&self) -> Option<u64> {
+        self.last_released
+    }
+
+    /// Returns true if the sequence was poisoned.
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
+    /// Returns true if a one-shot sequence completed its only cycle.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns true if the value was handed to a successor sequence.
+    pub fn is_moved(&self) -> bool {
+        self.moved
+    }
+}
@@ -1,24 +1,251 @@
+// Apart from the documented panics of `Unpoisoned` and the `build` constructors, and the
+// misuse checks of debug builds, no code path in the crate may panic, so that the fallible
+// API can be relied on where unwinding is not an option.
+#![warn(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::indexing_slicing,
+    clippy::unreachable
+)]
+
+use crate::{
+    adaptive::HoldTimes,
+    builder::Config,
+    dump::Waiting,
+    journal::Record,
+    misuse::LockedBy,
+    padded::CachePadded,
+    wait::{block, MAX_BACKOFF},
+    waiters::Waiters,
+};
+#[cfg(feature = "sync")]
+use crate::{
+    baton::Baton,
+    controller::Swaps,
+    deferred::Deferred,
+    events::{Callbacks, TurnSubscribers},
+    group::Groups,
+    heal::Heal,
+    history::History,
+    holder::HolderSlot,
+    hooks::Hooks,
+    mailbox::Mailboxes,
+    pi::PiLock,
+    rcu::Rcu,
+    rollback::Rollback,
+    schedule::Schedule,
+    snapshots::SnapshotSubscribers,
+};
 use std::{
+    borrow::{Borrow, BorrowMut},
     cell::UnsafeCell,
-    fmt,
+    fmt, iter,
+    mem::{self, MaybeUninit},
     ops::{Deref, DerefMut},
+    panic::{self, AssertUnwindSafe},
     sync::{
-        atomic::{AtomicU64, Ordering},
-        Arc,
-    }, thread, time::Duration,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, OnceLock,
+    },
+    thread,
+    time::Duration,
+};
+
+mod adaptive;
+#[cfg(feature = "sync")]
+mod baton;
+#[cfg(feature = "sync")]
+mod blocker;
+mod builder;
+#[cfg(feature = "sync")]
+mod completion;
+#[cfg(feature = "sync")]
+mod controller;
+#[cfg(feature = "sync")]
+mod cow;
+#[cfg(feature = "sync")]
+mod deferred;
+mod dump;
+#[cfg(feature = "sync")]
+mod duplicate;
+mod dyn_ticket;
+#[cfg(feature = "sync")]
+mod events;
+#[cfg(feature = "sync")]
+mod exclusive;
+#[cfg(feature = "sync")]
+mod external;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "sync")]
+mod group;
+#[cfg(feature = "sync")]
+mod heal;
+#[cfg(feature = "sync")]
+mod history;
+#[cfg(feature = "sync")]
+mod holder;
+#[cfg(feature = "sync")]
+mod hooks;
+#[cfg(feature = "async")]
+mod instrument;
+mod interrupt;
+mod journal;
+mod log;
+#[cfg(feature = "sync")]
+mod mailbox;
+mod metrics;
+mod misuse;
+mod multi;
+mod names;
+#[cfg(feature = "async")]
+mod notified;
+#[cfg(feature = "sync")]
+mod numa;
+#[cfg(feature = "sync")]
+mod observer;
+#[cfg(feature = "sync")]
+mod pacing;
+mod padded;
+#[cfg(feature = "sync")]
+mod pi;
+#[cfg(feature = "pipeline")]
+mod pipeline;
+#[cfg(feature = "sync")]
+mod process;
+mod raw;
+#[cfg(feature = "sync")]
+mod rcu;
+mod read_only;
+#[cfg(feature = "sync")]
+mod region;
+#[cfg(feature = "sync")]
+mod rollback;
+#[cfg(feature = "sync")]
+mod rotation;
+mod sanitize;
+#[cfg(feature = "sync")]
+mod schedule;
+#[cfg(feature = "sync")]
+mod sequenced;
+#[cfg(feature = "sync")]
+mod sequencer;
+#[cfg(feature = "sync")]
+mod set;
+#[cfg(feature = "sync")]
+mod snapshots;
+#[cfg(feature = "sync")]
+mod speculate;
+mod spin;
+mod status;
+#[cfg(feature = "test-util")]
+pub mod test;
+#[cfg(feature = "sync")]
+mod time_slice;
+mod timeline;
+#[cfg(feature = "async")]
+mod timeout;
+mod trace;
+#[cfg(feature = "sync")]
+mod transaction;
+mod unpoisoned;
+#[cfg(not(feature = "sync"))]
+mod unsynced;
+mod wait;
+mod waiters;
+
+#[cfg(feature = "sync")]
+pub use blocker::WouldBlock;
+pub use builder::{BuildError, Builder};
+#[cfg(feature = "sync")]
+pub use completion::CompletionHandle;
+#[cfg(feature = "sync")]
+pub use controller::Controller;
+#[cfg(feature = "sync")]
+pub use cow::CowGuard;
+pub use dump::StateDump;
+#[cfg(feature = "sync")]
+pub use duplicate::{Duplicate, DuplicateError, DuplicateGuard};
+pub use dyn_ticket::DynTicket;
+#[cfg(feature = "sync")]
+pub use events::Event;
+#[cfg(feature = "sync")]
+pub use exclusive::Exclusive;
+#[cfg(feature = "sync")]
+pub use external::{ExternalLock, Sequenced, SequencedGuard};
+#[cfg(feature = "sync")]
+pub use holder::Holder;
+#[cfg(feature = "sync")]
+pub use hooks::HookTurn;
+#[cfg(feature = "async")]
+pub use instrument::{set_instrument, Instrument, Resource, Wait};
+pub use interrupt::InterruptError;
+#[cfg(feature = "sync")]
+pub use journal::{replay, Replay};
+#[cfg(feature = "journal-log")]
+pub use log::{set_logger, Level, Logger};
+#[cfg(feature = "metrics")]
+pub use metrics::Metrics;
+pub use multi::{lock_all, LockAll};
+#[cfg(feature = "async")]
+pub use notified::Notified;
+#[cfg(feature = "sync")]
+pub use observer::{Observer, WeakSequex};
+#[cfg(feature = "sync")]
+pub use pi::{PiGuard, PiSequex};
+#[cfg(feature = "pipeline")]
+pub use pipeline::{
+    handoff_ring, resequence, ring_buffer, typed_pipeline, Collected, Delivery, HandoffStage,
+    Inlet, OrderedCollector, OrderedSink, OrderedWriter, Outlet, QueuedTask, Resequence,
+    RingConsumer, RingProducer, RingSlot, SequencedQueue, Stage, StageGuard, TypedPipeline,
+    TypedStage,
 };
+#[cfg(all(feature = "pipeline", feature = "async"))]
+pub use pipeline::{Arrival, ArrivalOrder, InArrivalOrder};
+#[cfg(feature = "sync")]
+pub use process::{AttachError, ProcessGuard, ProcessSequex};
+#[cfg(all(feature = "sync", feature = "async"))]
+pub use rcu::Changed;
+#[cfg(feature = "sync")]
+pub use rcu::{RcuReader, RcuSnapshot};
+pub use read_only::{ReadGuard, ReadOnly};
+#[cfg(feature = "sync")]
+pub use rotation::{Arbitration, Rotation, RotationGuard};
+#[cfg(feature = "sync")]
+pub use sequenced::SequencedLock;
+#[cfg(feature = "sync")]
+pub use sequencer::Sequencer;
+#[cfg(feature = "sync")]
+pub use set::{SequexSet, SetGuard};
+pub use status::{PoisonCause, PoisonReason, Status};
+#[cfg(feature = "sync")]
+pub use time_slice::{Overrun, OverrunPolicy};
+pub use timeline::{Timeline, TimelineGuard, Timestamp};
+#[cfg(feature = "async")]
+pub use timeout::{Delay, ThreadDelay, TimeoutError};
+#[cfg(feature = "trace")]
+pub use trace::Trace;
+#[cfg(feature = "sync")]
+pub use transaction::Transaction;
+pub use unpoisoned::Unpoisoned;
+pub use wait::WaitStrategy;
 
 /// A sequence-mutex lock, which guarantees locks are acquired in the order in which they
 /// were constructed, as opposed to the order in which locks are requested.
 pub struct Sequex<T> {
     ticket: u64,
+    member: u64,
     num_tickets: u64,
+    // Whether the handle is wrapped in a `ReadOnly`, whose turns hooks may only read.
+    read_only: bool,
     shared: Arc<Shared<T>>,
 }
 
 /// An RAII guard that releases the lock when dropped.
 pub struct Guard<'a, T> {
     sequex: &'a Sequex<T>,
+    mutated: bool,
 }
 
 /// An error returned when attempting to acquire a lock that was poisoned.
@@ -26,79 +253,1012 @@ pub struct Guard<'a, T> {
 pub struct SequexPoisoned;
 
 // Shared state of the lock.
-struct Shared<T> {
-    current: AtomicU64,
-    value: UnsafeCell<T>,
+pub(crate) struct Shared<T> {
+    // The ticket word every waiter polls, kept apart from the fields the holder writes.
+    current: CachePadded<AtomicU64>,
+    cycle: AtomicU64,
+    serving: AtomicU64,
+    #[cfg(feature = "sync")]
+    frame: AtomicU64,
+    turns: AtomicU64,
+    version: AtomicU64,
+    hold_times: HoldTimes,
+    poison_cause: OnceLock<PoisonCause>,
+    #[cfg(feature = "sync")]
+    rcu: OnceLock<Arc<Rcu<T>>>,
+    #[cfg(feature = "sync")]
+    swaps: Swaps<T>,
+    #[cfg(feature = "sync")]
+    rollback: Option<Rollback<T>>,
+    #[cfg(feature = "sync")]
+    heal: Option<Heal<T>>,
+    #[cfg(feature = "sync")]
+    history: Option<History<T>>,
+    #[cfg(feature = "sync")]
+    schedule: Schedule,
+    #[cfg(feature = "sync")]
+    baton: Baton,
+    #[cfg(feature = "sync")]
+    mailboxes: Mailboxes,
+    #[cfg(feature = "sync")]
+    groups: Groups,
+    num_tickets: u64,
+    live: AtomicU64,
+    retired: Box<[AtomicBool]>,
+    cycle_waiters: Waiters,
+    turn_waiters: Box<[Waiters]>,
+    waiting: Waiting,
+    #[cfg(feature = "sync")]
+    callbacks: Callbacks,
+    #[cfg(feature = "sync")]
+    hooks: Hooks<T>,
+    #[cfg(feature = "sync")]
+    turn_subscribers: TurnSubscribers,
+    #[cfg(feature = "sync")]
+    snapshot_subscribers: SnapshotSubscribers<T>,
+    #[cfg(feature = "sync")]
+    holder: HolderSlot,
+    locked_by: LockedBy,
+    #[cfg(feature = "sync")]
+    deferred: Deferred,
+    #[cfg(feature = "sync")]
+    deferring: AtomicBool,
+    #[cfg(feature = "sync")]
+    pi: PiLock,
+    #[cfg(feature = "async")]
+    resource: u64,
+    config: Config,
+    initialized: AtomicBool,
+    init: UnsafeCell<Option<Init<T>>>,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// Constructs a lazily initialized value.
+pub(crate) type Init<T> = Box<dyn FnOnce() -> T + Send>;
+
+// The value a sequence is created with.
+pub(crate) enum Value<T> {
+    Ready(T),
+    Lazy(Init<T>),
 }
 
 // Marker value to represent when the resource is locked.
-const LOCKED: u64 = u64::MAX;
+pub(crate) const LOCKED: u64 = u64::MAX;
 
 // Marker value to represent when the resource is poisoned.
-const POISON: u64 = u64::MAX - 1;
+pub(crate) const POISON: u64 = u64::MAX - 1;
+
+// Marker value to represent when the value has been moved to a successor sequence.
+pub(crate) const MOVED: u64 = u64::MAX - 2;
+
+// Marker value to represent when a one-shot sequence has completed its only cycle.
+pub(crate) const FINISHED: u64 = u64::MAX - 3;
+
+// Returns true if the state still hands out turns, ie. it is a ticket or locked.
+pub(crate) fn is_live(current: u64) -> bool {
+    current == LOCKED || current < FINISHED
+}
+
+// The value is only ever accessed by the holder of the lock, so the shared state may be sent
+// and shared between threads as long as the value itself can be sent.
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+// Guards hand out `&T`, so sharing one between threads requires `T: Sync`.
+unsafe impl<'a, T: Send + Sync> Sync for Guard<'a, T> {}
 
 impl<T> Sequex<T> {
-    /// Create a new sequence that wrap an internval value.
+    /// Create a new sequence that wrap an internval value, with the default configuration.
+    /// See [Sequex::builder] for more options.
     pub fn new(value: T, num_tickets: u64) -> Vec<Self> {
-        let shared = Arc::new(Shared {
-            current: AtomicU64::new(0),
-            value: UnsafeCell::new(value),
-        });
-        (0..num_tickets)
-            .map(|ticket| {
-                let shared = shared.clone();
-                Self {
-                    ticket,
-                    num_tickets,
-                    shared,
-                }
-            })
-            .collect()
+        Self::builder(value, num_tickets).build()
+    }
+
+    /// Create a new sequence of `N` tickets as an array, so they can be destructured with
+    /// `let [a, b, c] = Sequex::new_array(value)`. See [Builder::build_array].
+    pub fn new_array<const N: usize>(value: T) -> [Self; N] {
+        Self::builder(value, N as u64).build_array()
+    }
+
+    /// Create a new sequence whose value is constructed by `init` when the lock is first
+    /// acquired, so expensive resources aren't built until the sequence actually starts. If
+    /// `init` panics, the lock is poisoned. Snapshots and other reads that don't take a turn
+    /// wait for the value to be constructed rather than constructing it.
+    pub fn new_with(init: impl FnOnce() -> T + Send + 'static, num_tickets: u64) -> Vec<Self> {
+        Self::builder_with(init, num_tickets).build()
+    }
+
+    /// Returns the position of this ticket in the sequence.
+    pub fn ticket(&self) -> u64 {
+        self.ticket
+    }
+
+    /// Returns a raw pointer to the protected value, for passing its address to foreign code.
+    /// The pointer is valid while any handle to the sequence is alive, but dereferencing it
+    /// is only sound while this ticket holds the lock. A lazily initialized value is
+    /// uninitialized until the lock is first acquired.
+    pub fn data_ptr(&self) -> *mut T {
+        self.shared.value.get().cast()
+    }
+
+    /// Returns the number of ticket handles of this sequence that are still alive.
+    pub fn ticket_count(&self) -> u64 {
+        self.shared.ticket_count()
+    }
+
+    /// Returns the positions of the tickets whose handles have been dropped, in order.
+    pub fn retired_tickets(&self) -> Vec<u64> {
+        self.shared.retired_tickets()
+    }
+
+    /// Returns true if a one-shot sequence has completed its only cycle.
+    pub fn is_complete(&self) -> bool {
+        self.shared.is_complete()
+    }
+
+    /// Returns the number of turns that remain until the current cycle completes, counting
+    /// a turn that is in progress. For one-shot sequences this is the number of turns until
+    /// the sequence finishes.
+    pub fn remaining_turns(&self) -> u64 {
+        self.shared.remaining_turns()
     }
 
     /// Attempt to acquire the lock. Does not block the current thread if the lock could
-    /// not be acquired. Returns [SequencePoisoned] if the lock was poisoned.
+    /// not be acquired. Returns [SequexPoisoned] if the lock was poisoned or the sequence has
+    /// finished.
     pub fn try_lock(&self) -> Result<Option<Guard<'_, T>>, SequexPoisoned> {
+        if !self.ready()? {
+            return Ok(None);
+        }
+        #[cfg(feature = "test-util")]
+        if let Some(faults) = &self.shared.config.faults {
+            if faults.fail_try_lock(self.ticket) {
+                return Ok(None);
+            }
+        }
         match self.shared.current.compare_exchange(
             self.ticket,
             LOCKED,
             Ordering::SeqCst,
             Ordering::SeqCst,
         ) {
-            Ok(_) => Ok(Some(Guard { sequex: self })),
-            Err(current) if current == POISON => Err(SequexPoisoned),
+            Ok(_) => {
+                self.shared.annotate_acquire();
+                self.shared.start_pace();
+                self.shared.start_scheduled(self.ticket);
+                self.shared.pass_baton();
+                self.shared.deliver_mail(self.ticket);
+                #[cfg(feature = "test-util")]
+                if let Some(faults) = &self.shared.config.faults {
+                    if faults.poisons(self.ticket, self.shared.cycle.load(Ordering::SeqCst)) {
+                        self.shared
+                            .poison(PoisonCause::new(Some(self.ticket), PoisonReason::Injected));
+                        return Err(SequexPoisoned);
+                    }
+                    faults.before_turn(self.ticket);
+                }
+                self.shared.pi_acquire();
+                unsafe {
+                    self.shared.initialize();
+                    self.shared.heal_pending();
+                    self.shared.save_rollback();
+                }
+                self.shared.serving.store(self.ticket, Ordering::Relaxed);
+                self.shared.set_holder(Some(self.ticket));
+                self.shared.start_slice(self.ticket);
+                self.shared.trace_acquire(self.ticket);
+                self.shared.measure_acquire();
+                self.shared.time_acquire();
+                self.shared.record(Record::Acquire {
+                    ticket: self.ticket,
+                    cycle: self.shared.cycle.load(Ordering::SeqCst),
+                });
+                let mut guard = Guard {
+                    sequex: self,
+                    mutated: false,
+                };
+                // A hook that panicked poisoned the sequence, and dropping the guard undoes
+                // the bookkeeping of the turn.
+                guard.mutated =
+                    unsafe { self.shared.run_acquire_hooks(self.ticket, self.read_only)? };
+                Ok(Some(guard))
+            }
+            Err(current) if !is_live(current) => Err(SequexPoisoned),
             Err(_) => Ok(None),
         }
     }
 
+    // Returns true if this ticket could take its turn right now, without any of the side
+    // effects of taking it.
+    pub(crate) fn ready(&self) -> Result<bool, SequexPoisoned> {
+        // The cycle can't change during this ticket's turn, so it must be checked against the
+        // frame only once the turn has been observed.
+        let current = self.shared.current.load(Ordering::SeqCst);
+        if current != self.ticket
+            || !self.shared.frame_open()
+            || self.shared.quorum_blocks(self.ticket, self.member)
+            || self.shared.start_delay(self.ticket).is_some()
+        {
+            return if is_live(current) {
+                Ok(false)
+            } else {
+                Err(SequexPoisoned)
+            };
+        }
+        Ok(true)
+    }
+
     /// Acquire a lock, blocking the current thread if it could not be acquired. Returns a
-    /// [SequencePoisoned] if the lock was poisoned.
+    /// [SequexPoisoned] if the lock was poisoned or the sequence has finished.
+    ///
+    /// In debug builds, panics if the current thread already holds the lock of this sequence,
+    /// which would deadlock. Dropping a ticket while the current thread holds the lock of a
+    /// sequence that poisons panics too.
     pub fn lock(&self) -> Result<Guard<'_, T>, SequexPoisoned> {
-        let mut backoff = 100;
+        self.lock_unless(|| false).map_err(|_| SequexPoisoned)
+    }
+
+    // Block until the lock is acquired like `lock`, giving up once `interrupted` returns true.
+    pub(crate) fn lock_unless(
+        &self,
+        interrupted: impl Fn() -> bool,
+    ) -> Result<Guard<'_, T>, InterruptError> {
+        self.shared.check_lock(self.ticket);
+        let _waiting = self.shared.waiting.enter(self.ticket);
+        let since = self.shared.wait_started();
+        let guard = block(
+            self.shared.config.wait_strategy,
+            Some(&self.shared.current),
+            Some(&self.shared.hold_times),
+            || loop {
+                if interrupted() {
+                    return Ok(Some(None));
+                }
+                if let Some(guard) = self.try_lock()? {
+                    return Ok(Some(Some(guard)));
+                }
+                // Under `WaitStrategy::Spin`, a scheduled or paced turn is spun for like any
+                // other, instead of sleeping or waiting on the holder in the kernel.
+                let spins = self.shared.config.wait_strategy == WaitStrategy::Spin;
+                if let Some(remaining) = self.shared.start_delay(self.ticket) {
+                    if spins {
+                        return Ok(None);
+                    }
+                    // Slept in slices, so that an interrupt is noticed while the turn waits.
+                    thread::sleep(remaining.min(Duration::from_micros(MAX_BACKOFF)));
+                    continue;
+                }
+                self.shared.check_slice();
+                if spins || !self.shared.wait_for_holder() {
+                    return Ok(None);
+                }
+            },
+        )?
+        .ok_or(InterruptError::Interrupted)?;
+        self.shared.trace_wait(self.ticket, since);
+        self.shared.measure_wait(since);
+        Ok(guard)
+    }
+
+    /// Acquire a lock once it is this ticket's turn, and keep it only if `predicate` holds for
+    /// the value. Otherwise the turn is passed to the next ticket immediately and `None` is
+    /// returned. Returns [SequexPoisoned] if the lock was poisoned or the sequence has
+    /// finished.
+    pub fn lock_if(
+        &self,
+        predicate: impl FnOnce(&T) -> bool,
+    ) -> Result<Option<Guard<'_, T>>, SequexPoisoned> {
+        let guard = self.lock()?;
+        Ok(predicate(&guard).then_some(guard))
+    }
+
+    /// Wait for this ticket's turn and pass it to the next ticket without accessing the value.
+    /// Returns [SequexPoisoned] if the lock was poisoned or the sequence has finished.
+    pub fn pass(&self) -> Result<(), SequexPoisoned> {
+        self.lock().map(drop)
+    }
+
+    /// Block the current thread until it is this ticket's turn, without acquiring the lock.
+    /// Returns [SequexPoisoned] if the lock was poisoned or the sequence has finished. Panics
+    /// in debug builds like [Sequex::lock].
+    pub fn wait_for_turn(&self) -> Result<(), SequexPoisoned> {
+        self.shared.check_lock(self.ticket);
+        let _waiting = self.shared.waiting.enter(self.ticket);
+        block(
+            self.shared.config.wait_strategy,
+            Some(&self.shared.current),
+            Some(&self.shared.hold_times),
+            || match self.shared.current.load(Ordering::SeqCst) {
+                current if current == self.ticket => Ok(Some(())),
+                current if is_live(current) => {
+                    self.shared.check_slice();
+                    Ok(None)
+                }
+                _ => Err(SequexPoisoned),
+            },
+        )
+    }
+
+    /// Poison the sequence, waking every ticket with [SequexPoisoned], to shut it down. A lock
+    /// that is held stays valid until its guard is dropped. Has no effect if the sequence has
+    /// already ended.
+    pub fn poison(&self) {
+        self.shared
+            .poison(PoisonCause::new(Some(self.ticket), PoisonReason::Requested));
+    }
+
+    /// Acquire a lock and run `f` on the value, releasing the lock once it returns. If `f`
+    /// panics, the panic is caught, the lock is poisoned and [SequexPoisoned] is returned, so
+    /// the lock can never outlive the closure. If the sequence was built without poisoning,
+    /// the turn is passed on and the panic resumed instead, as it is once the value has been
+    /// replaced by a sequence that heals.
+    pub fn lock_with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R, SequexPoisoned> {
+        self.run_with(self.lock()?, |guard| f(guard))
+    }
+
+    // Run `f` on a turn that is already held, releasing it once `f` returns. A panic in `f`
+    // rolls back, heals or poisons the sequence as configured.
+    pub(crate) fn run_with<R>(
+        &self,
+        mut guard: Guard<'_, T>,
+        f: impl FnOnce(&mut Guard<'_, T>) -> R,
+    ) -> Result<R, SequexPoisoned> {
+        match panic::catch_unwind(AssertUnwindSafe(|| f(&mut guard))) {
+            Ok(result) => Ok(result),
+            Err(payload) if self.shared.rolls_back() => {
+                unsafe { self.shared.roll_back() };
+                guard.mutated = false;
+                drop(guard);
+                panic::resume_unwind(payload)
+            }
+            Err(payload) if !self.shared.config.poisoning => {
+                drop(guard);
+                panic::resume_unwind(payload)
+            }
+            Err(payload) if self.shared.heals() => {
+                unsafe { self.shared.heal_now() };
+                guard.mutated = false;
+                drop(guard);
+                panic::resume_unwind(payload)
+            }
+            Err(_) => {
+                self.shared
+                    .poison(PoisonCause::new(Some(self.ticket), PoisonReason::Panicked));
+                Err(SequexPoisoned)
+            }
+        }
+    }
+
+    /// Clone the current value without taking a turn. The lock is held only for the duration
+    /// of the clone and is handed back to whichever ticket was being served, so the ticket
+    /// order is never advanced. Blocks while another ticket holds the lock, and while a lazily
+    /// constructed value has yet to be built by the first turn. The value of a
+    /// finished one-shot sequence can still be cloned. Returns [SequexPoisoned] if the lock
+    /// was poisoned.
+    pub fn snapshot(&self) -> Result<T, SequexPoisoned>
+    where
+        T: Clone,
+    {
+        self.shared.snapshot()
+    }
+
+    /// Clone the current value like [Sequex::snapshot], along with its version. See
+    /// [Guard::version].
+    pub fn versioned_snapshot(&self) -> Result<(T, u64), SequexPoisoned>
+    where
+        T: Clone,
+    {
+        self.shared.versioned_snapshot()
+    }
+
+    /// Returns the current version of the value, without locking. A cache of the value can
+    /// compare it to the version it was taken at to detect that it is stale. See
+    /// [Guard::version].
+    pub fn version(&self) -> u64 {
+        self.shared.version.load(Ordering::SeqCst)
+    }
+}
+
+// Poisons the lock unless it is forgotten, eg. if the holder panics.
+struct PoisonOnUnwind<'a, T>(&'a Shared<T>);
+
+impl<T> Drop for PoisonOnUnwind<'_, T> {
+    fn drop(&mut self) {
+        self.0
+            .poison(PoisonCause::new(None, PoisonReason::Panicked));
+    }
+}
+
+// Hands the lock back to a ticket without advancing the order, even if the holder panics.
+// A peek took the lock without taking a turn, so there is no bookkeeping of a turn to end.
+struct Restore<'a, T> {
+    shared: &'a Shared<T>,
+    ticket: u64,
+    peeked: bool,
+}
+
+impl<T> Drop for Restore<'_, T> {
+    fn drop(&mut self) {
+        self.shared.annotate_release();
+        if let Err(actual) = self.shared.current.compare_exchange(
+            LOCKED,
+            self.ticket,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        ) {
+            self.shared.lost_handoff(self.ticket, actual);
+        } else {
+            if !self.peeked {
+                self.shared.set_holder(None);
+                self.shared.trace_release();
+                self.shared.measure_release();
+                self.shared.end_slice();
+                self.shared.record(Record::Return {
+                    ticket: self.ticket,
+                });
+            }
+            self.shared.notify_turn(self.ticket);
+            self.shared.skip_retired(self.ticket);
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    // Poison the lock, waking every ticket with an error. A sequence that already ended stays
+    // that way.
+    fn poison(&self, cause: PoisonCause) {
+        if self
+            .current
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+                is_live(current).then_some(POISON)
+            })
+            .is_ok()
+        {
+            let _ = self.poison_cause.set(cause);
+            self.record(Record::Poisoned);
+            self.measure_poison();
+            self.notify_ended();
+            #[cfg(feature = "sync")]
+            self.callbacks.notify(Event::Poisoned);
+        }
+    }
+
+    // Write a record to the journal, if the sequence keeps one.
+    fn record(&self, record: Record) {
+        self.log_record(&record);
+        #[cfg(feature = "sync")]
+        if let Some(journal) = &self.config.journal {
+            journal.record(record);
+        }
+    }
+
+    // Returns true if the current cycle may run under the frame it has been advanced to.
+    fn frame_open(&self) -> bool {
+        #[cfg(feature = "sync")]
+        if self.cycle.load(Ordering::SeqCst) > self.frame.load(Ordering::SeqCst) {
+            return false;
+        }
+        true
+    }
+
+    // Returns true if the turns of retired tickets are passed on, rather than the sequence
+    // being poisoned when they are dropped.
+    fn skips_retired(&self) -> bool {
+        !self.config.poisoning || self.rolls_back() || self.heals()
+    }
+
+    // Bump the version of the value at the end of a turn that accessed it mutably, and
+    // publish it to readers. Must only be called while holding the lock.
+    fn bump_version(&self) {
+        let version = self.version.fetch_add(1, Ordering::SeqCst) + 1;
+        self.publish(version);
+    }
+
+    // Wake everything waiting for it to be `ticket`'s turn.
+    fn notify_turn(&self, ticket: u64) {
+        wait::notify(&self.current);
+        if let Some(waiters) = self.turn_waiters.get(ticket as usize) {
+            waiters.wake_all();
+            #[cfg(feature = "sync")]
+            self.turn_subscribers.notify(Some(ticket));
+        }
+    }
+
+    // Wake everything waiting on a sequence that ended, so it can observe the error.
+    fn notify_ended(&self) {
+        wait::notify(&self.current);
+        self.cycle_waiters.wake_all();
+        for waiters in self.turn_waiters.iter() {
+            waiters.wake_all();
+        }
+        #[cfg(feature = "sync")]
+        {
+            self.turn_subscribers.notify(None);
+            self.end_snapshots();
+            if let Some(rcu) = self.rcu.get() {
+                rcu.end();
+            }
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    fn new(value: Value<T>, num_tickets: u64, config: Config) -> Result<Self, BuildError> {
+        if num_tickets >= FINISHED {
+            return Err(BuildError::TooManyTickets);
+        }
+        let len = usize::try_from(num_tickets).map_err(|_| BuildError::TooManyTickets)?;
+        names::check(&config.names, num_tickets)?;
+        let retired = try_boxed_slice(len, || AtomicBool::new(false))?;
+        let turn_waiters = try_boxed_slice(len, Waiters::default)?;
+        #[cfg(feature = "sync")]
+        let schedule = Schedule::new(len)?;
+        let waiting = Waiting::new(len)?;
+        #[cfg(feature = "sync")]
+        let mailboxes = Mailboxes::new(len)?;
+        #[cfg(feature = "sync")]
+        let groups = Groups::new(len)?;
+        let (value, init) = match value {
+            Value::Ready(value) => (MaybeUninit::new(value), None),
+            Value::Lazy(init) => (MaybeUninit::uninit(), Some(init)),
+        };
+        Ok(Self {
+            current: CachePadded(AtomicU64::new(0)),
+            cycle: AtomicU64::new(0),
+            serving: AtomicU64::new(0),
+            #[cfg(feature = "sync")]
+            frame: AtomicU64::new(if config.frame_gated { 0 } else { u64::MAX }),
+            turns: AtomicU64::new(0),
+            version: AtomicU64::new(0),
+            hold_times: HoldTimes::default(),
+            poison_cause: OnceLock::new(),
+            #[cfg(feature = "sync")]
+            rcu: OnceLock::new(),
+            #[cfg(feature = "sync")]
+            swaps: Swaps::default(),
+            #[cfg(feature = "sync")]
+            rollback: None,
+            #[cfg(feature = "sync")]
+            heal: None,
+            #[cfg(feature = "sync")]
+            history: None,
+            #[cfg(feature = "sync")]
+            schedule,
+            #[cfg(feature = "sync")]
+            baton: Baton::default(),
+            #[cfg(feature = "sync")]
+            mailboxes,
+            #[cfg(feature = "sync")]
+            groups,
+            num_tickets,
+            live: AtomicU64::new(num_tickets),
+            retired,
+            cycle_waiters: Waiters::default(),
+            turn_waiters,
+            waiting,
+            #[cfg(feature = "sync")]
+            callbacks: Callbacks::default(),
+            #[cfg(feature = "sync")]
+            hooks: Hooks::default(),
+            #[cfg(feature = "sync")]
+            turn_subscribers: TurnSubscribers::default(),
+            #[cfg(feature = "sync")]
+            snapshot_subscribers: SnapshotSubscribers::default(),
+            #[cfg(feature = "sync")]
+            holder: HolderSlot::default(),
+            locked_by: LockedBy::default(),
+            #[cfg(feature = "sync")]
+            deferred: Deferred::default(),
+            #[cfg(feature = "sync")]
+            deferring: AtomicBool::new(false),
+            #[cfg(feature = "sync")]
+            pi: PiLock::default(),
+            #[cfg(feature = "async")]
+            resource: instrument::create_resource(num_tickets),
+            config,
+            initialized: AtomicBool::new(init.is_none()),
+            init: UnsafeCell::new(init),
+            value: UnsafeCell::new(value),
+        })
+    }
+
+    pub(crate) fn ticket_count(&self) -> u64 {
+        self.live.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn retired_tickets(&self) -> Vec<u64> {
+        (0..)
+            .zip(self.retired.iter())
+            .filter(|(_, retired)| retired.load(Ordering::SeqCst))
+            .map(|(ticket, _)| ticket)
+            .collect()
+    }
+
+    pub(crate) fn is_complete(&self) -> bool {
+        self.current.load(Ordering::SeqCst) == FINISHED
+    }
+
+    pub(crate) fn remaining_turns(&self) -> u64 {
+        if self.is_complete() {
+            return 0;
+        }
+        self.num_tickets - self.turns.load(Ordering::SeqCst) % self.num_tickets
+    }
+
+    pub(crate) fn snapshot(&self) -> Result<T, SequexPoisoned>
+    where
+        T: Clone,
+    {
+        self.versioned_snapshot().map(|(value, _)| value)
+    }
+
+    // Clone the value along with the version it was cloned at.
+    pub(crate) fn versioned_snapshot(&self) -> Result<(T, u64), SequexPoisoned>
+    where
+        T: Clone,
+    {
+        self.peek(|value, version| (value.clone(), version))
+    }
+
+    // Run `f` on the value and its version while no ticket holds the lock, blocking until
+    // then, without taking a turn.
+    pub(crate) fn peek<R>(&self, mut f: impl FnMut(&T, u64) -> R) -> Result<R, SequexPoisoned> {
+        block(
+            self.config.wait_strategy,
+            Some(&self.current),
+            Some(&self.hold_times),
+            || self.try_peek(&mut f),
+        )
+    }
+
+    // Attempt to run `f` on the value while no ticket holds the lock.
+    // A lazily constructed value is left for the first holder of the lock to build, so there
+    // is nothing to peek at until it has.
+    fn try_peek<R>(&self, f: &mut impl FnMut(&T, u64) -> R) -> Result<Option<R>, SequexPoisoned> {
+        let current = match self.current.load(Ordering::SeqCst) {
+            POISON | MOVED => return Err(SequexPoisoned),
+            LOCKED => return Ok(None),
+            current => current,
+        };
+        if !self.initialized.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+        if self
+            .current
+            .compare_exchange(current, LOCKED, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Ok(None);
+        }
+        self.annotate_acquire();
+        let _restore = Restore {
+            shared: self,
+            ticket: current,
+            peeked: true,
+        };
+        unsafe {
+            let value = (*self.value.get()).assume_init_ref();
+            Ok(Some(f(value, self.version.load(Ordering::SeqCst))))
+        }
+    }
+
+    // Construct a lazily initialized value, poisoning the lock if that panics. Must only be
+    // called while holding the lock.
+    unsafe fn initialize(&self) {
+        if self.initialized.load(Ordering::SeqCst) {
+            return;
+        }
+        if let Some(init) = (*self.init.get()).take() {
+            let poison = PoisonOnUnwind(self);
+            (*self.value.get()).write(init());
+            mem::forget(poison);
+            self.initialized.store(true, Ordering::SeqCst);
+        }
+    }
+
+    // Release the lock held by `ticket`, passing the turn to the next ticket. The last ticket
+    // of a one-shot sequence finishes it instead.
+    fn release(&self, ticket: u64, num_tickets: u64) {
+        #[cfg(feature = "test-util")]
+        if let Some(faults) = &self.config.faults {
+            faults.after_turn(ticket);
+        }
+        self.set_holder(None);
+        self.record(Record::Release {
+            ticket,
+            cycle: self.cycle.load(Ordering::SeqCst),
+        });
+        self.pi_release();
+        self.trace_release();
+        self.measure_release();
+        self.time_release();
+        if self.end_slice() {
+            return;
+        }
+        let (mut next, wrapped) = if self.slice_passed() {
+            self.skip_cycle(ticket, num_tickets)
+        } else {
+            self.next_turn(ticket, num_tickets)
+        };
+        if wrapped {
+            self.save_history();
+            self.send_snapshots();
+            self.apply_swaps();
+            self.send_broadcasts();
+        }
+        let finish = wrapped && self.config.one_shot;
+        if finish {
+            next = FINISHED;
+        }
+        let cycle = wrapped.then(|| {
+            let cycle = self.cycle.fetch_add(1, Ordering::SeqCst);
+            self.record(Record::Cycle(cycle + 1));
+            cycle
+        });
+        self.annotate_release();
+        let released =
+            match self
+                .current
+                .compare_exchange(LOCKED, next, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => true,
+                Err(actual) => {
+                    self.lost_handoff(ticket, actual);
+                    false
+                }
+            };
+        #[cfg_attr(not(feature = "sync"), allow(unused_variables))]
+        if let Some(cycle) = cycle {
+            self.measure_cycle();
+            self.cycle_waiters.wake_all();
+            #[cfg(feature = "sync")]
+            self.callbacks.notify(Event::CycleComplete(cycle));
+        }
+        if finish && released {
+            self.record(Record::Finished);
+            self.notify_ended();
+            #[cfg(feature = "sync")]
+            self.callbacks.notify(Event::Finished);
+        } else if released {
+            self.notify_turn(next);
+        }
+    }
+
+    // Returns the ticket whose turn follows `ticket`, and whether the order wrapped around to
+    // start a new cycle. Retired tickets are skipped unless the sequence poisons.
+    fn next_ticket(&self, ticket: u64, num_tickets: u64) -> (u64, bool) {
+        let mut next = ticket;
+        let mut wrapped = false;
+        loop {
+            next += 1;
+            if next == num_tickets {
+                next = 0;
+                wrapped = true;
+            }
+            self.turns.fetch_add(1, Ordering::SeqCst);
+            if !self.skips_retired()
+                || next == ticket
+                || !self
+                    .retired
+                    .get(next as usize)
+                    .is_some_and(|retired| retired.load(Ordering::SeqCst))
+            {
+                return (next, wrapped);
+            }
+            self.log_skip(next);
+        }
+    }
+
+    // Returns the first ticket of the next cycle, skipping the turns that remain in this one
+    // after `ticket`'s.
+    fn skip_cycle(&self, ticket: u64, num_tickets: u64) -> (u64, bool) {
+        let mut ticket = ticket;
         loop {
-            if let Some(guard) = self.try_lock()? {
-                return Ok(guard);
+            let (next, wrapped) = self.next_turn(ticket, num_tickets);
+            if wrapped {
+                return (next, true);
             }
-            thread::park_timeout(Duration::from_micros(backoff));
-            backoff *= 2;
+            self.log_skip(next);
+            ticket = next;
+        }
+    }
+
+    // Pass on the turn of a retired ticket if it is currently its turn, when the sequence
+    // does not poison.
+    fn skip_retired(&self, ticket: u64) {
+        let retired = self
+            .retired
+            .get(ticket as usize)
+            .is_some_and(|retired| retired.load(Ordering::SeqCst));
+        if !self.skips_retired() || !retired {
+            return;
+        }
+        if self
+            .current
+            .compare_exchange(ticket, LOCKED, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            self.pi_acquire();
+            self.release(ticket, self.num_tickets);
+        }
+    }
+}
+
+// Allocate a slice of `len` elements, returning an error instead of aborting if the
+// allocation fails.
+pub(crate) fn try_boxed_slice<E>(len: usize, f: impl FnMut() -> E) -> Result<Box<[E]>, BuildError> {
+    let mut elements = Vec::new();
+    elements
+        .try_reserve_exact(len)
+        .map_err(|_| BuildError::OutOfMemory)?;
+    elements.extend(iter::repeat_with(f).take(len));
+    Ok(elements.into_boxed_slice())
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        #[cfg(feature = "async")]
+        instrument::drop_resource(self.resource);
+        #[cfg(feature = "sync")]
+        if let Some(rcu) = self.rcu.get() {
+            rcu.end();
+        }
+        if *self.initialized.get_mut() && *self.current.get_mut() != MOVED {
+            unsafe { self.value.get_mut().assume_init_drop() }
         }
     }
 }
 
 impl<T> Drop for Sequex<T> {
     fn drop(&mut self) {
-        self.shared.current.store(POISON, Ordering::SeqCst);
+        if self.shared.leave_group(self.ticket) {
+            return;
+        }
+        self.shared.check_drop(self.ticket);
+        if let Some(retired) = self.shared.retired.get(self.ticket as usize) {
+            retired.store(true, Ordering::SeqCst);
+        }
+        self.shared.live.fetch_sub(1, Ordering::SeqCst);
+        // A sequence that rolls back a panicking turn also survives the panicking thread
+        // dropping its ticket, and one that heals survives any ticket being dropped.
+        if self.shared.heals() && self.shared.config.poisoning {
+            self.shared.heal_later();
+            self.shared.skip_retired(self.ticket);
+        } else if self.shared.config.poisoning && !(self.shared.rolls_back() && thread::panicking())
+        {
+            self.shared.poison(PoisonCause::new(
+                Some(self.ticket),
+                PoisonReason::TicketDropped,
+            ));
+        } else {
+            self.shared.skip_retired(self.ticket);
+        }
+    }
+}
+
+impl<'a, T> Guard<'a, T> {
+    /// Returns the cycle this turn belongs to, counting from zero. The cycle advances each
+    /// time the last ticket releases its lock.
+    pub fn cycle(&self) -> u64 {
+        self.sequex.shared.cycle.load(Ordering::SeqCst)
+    }
+
+    /// Returns the version of the value, which is bumped each time a guard that accessed the
+    /// value mutably releases the lock. Changes made through this guard are counted once it
+    /// is dropped.
+    pub fn version(&self) -> u64 {
+        self.sequex.shared.version.load(Ordering::SeqCst)
+    }
+
+    /// Open an inner sequence over the locked value, scoped to this turn. The inner tickets
+    /// borrow the guard, so the outer turn can only be released once every inner ticket has
+    /// been dropped, allowing one stage to fan out to several ordered sub-workers.
+    pub fn sub_sequence(&mut self, num_tickets: u64) -> Vec<Sequex<&mut T>> {
+        Sequex::new(&mut **self, num_tickets)
+    }
+
+    /// End this sequence and replace it with a successor of `num_tickets` tickets that
+    /// inherits the value, allowing a pipeline to be reconfigured between cycles. The
+    /// successor starts at its first ticket, and is built with the options of the [Builder]
+    /// this sequence was built with, apart from its journal, its tracer and priority
+    /// inheritance, and the names of tickets it no longer has. Whatever was registered on the
+    /// tickets of this sequence, such as hooks, callbacks and subscriptions, stays behind.
+    /// The turn is released like any other first, running the release hooks of this
+    /// sequence. Any remaining tickets of this sequence will return [SequexPoisoned] from then
+    /// on. Returns [SequexPoisoned] if the lock was poisoned in the meantime, including by a
+    /// release hook, in which case no successor is created.
+    pub fn into_successor(mut self, num_tickets: u64) -> Result<Vec<Sequex<T>>, SequexPoisoned> {
+        let shared = &*self.sequex.shared;
+        if matches!(
+            unsafe { shared.run_release_hooks(self.sequex.ticket, self.sequex.read_only) },
+            Ok(true)
+        ) {
+            self.mutated = true;
+        }
+        drop(unsafe { shared.take_rollback() });
+        if self.mutated {
+            shared.bump_version();
+        }
+        let moved =
+            shared
+                .current
+                .compare_exchange(LOCKED, MOVED, Ordering::SeqCst, Ordering::SeqCst);
+        shared.set_holder(None);
+        shared.trace_release();
+        shared.measure_release();
+        shared.time_release();
+        shared.end_slice();
+        shared.pi_release();
+        mem::forget(self);
+        moved.map_err(|_| SequexPoisoned)?;
+        let value = unsafe { (*shared.value.get()).assume_init_read() };
+        shared.record(Record::Moved);
+        shared.notify_ended();
+        #[cfg(feature = "sync")]
+        shared.callbacks.notify(Event::Finished);
+        Ok(Builder::successor(shared, value, num_tickets).build())
+    }
+
+    // Hand the turn back to this guard's ticket without advancing the order.
+    pub(crate) fn give_back(mut self) {
+        if matches!(
+            unsafe {
+                self.sequex
+                    .shared
+                    .run_release_hooks(self.sequex.ticket, self.sequex.read_only)
+            },
+            Ok(true)
+        ) {
+            self.mutated = true;
+        }
+        drop(unsafe { self.sequex.shared.take_rollback() });
+        if self.mutated {
+            self.sequex.shared.bump_version();
+        }
+        self.sequex.shared.pi_release();
+        let restore = Restore {
+            shared: &*self.sequex.shared,
+            ticket: self.sequex.ticket,
+            peeked: false,
+        };
+        mem::forget(self);
+        drop(restore);
     }
 }
 
 impl<'a, T> Drop for Guard<'a, T> {
     fn drop(&mut self) {
-        let next = (self.sequex.ticket + 1) % self.sequex.num_tickets;
-        self.sequex
-            .shared
-            .current
-            .compare_exchange(LOCKED, next, Ordering::SeqCst, Ordering::SeqCst)
-            .ok();
+        let sequex = self.sequex;
+        unsafe {
+            if !thread::panicking()
+                && matches!(
+                    sequex
+                        .shared
+                        .run_release_hooks(sequex.ticket, sequex.read_only),
+                    Ok(true)
+                )
+            {
+                self.mutated = true;
+            }
+            if thread::panicking() && sequex.shared.roll_back() {
+                self.mutated = false;
+            }
+            drop(sequex.shared.take_rollback());
+        }
+        if self.mutated {
+            sequex.shared.bump_version();
+        }
+        let shared = &*sequex.shared;
+        if shared.reach_quorum(sequex.ticket, sequex.member, thread::panicking()) {
+            shared.release(sequex.ticket, sequex.num_tickets);
+        } else {
+            shared.pi_release();
+            drop(Restore {
+                shared,
+                ticket: sequex.ticket,
+                peeked: false,
+            });
+        }
     }
 }
 
@@ -108,15 +1268,54 @@ impl fmt::Display for SequexPoisoned {
     }
 }
 
+impl std::error::Error for SequexPoisoned {}
+
 impl<'a, T> Deref for Guard<'a, T> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
-        unsafe { &*self.sequex.shared.value.get() }
+        unsafe { (*self.sequex.shared.value.get()).assume_init_ref() }
     }
 }
 
 impl<'a, T> DerefMut for Guard<'a, T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe { &mut *self.sequex.shared.value.get() }
+        self.mutated = true;
+        unsafe { (*self.sequex.shared.value.get()).assume_init_mut() }
+    }
+}
+
+impl<T> AsRef<T> for Guard<'_, T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T> AsMut<T> for Guard<'_, T> {
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T> Borrow<T> for Guard<'_, T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T> BorrowMut<T> for Guard<'_, T> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Guard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Guard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
     }
 }
@@ -0,0 +1,115 @@
+use crate::{journal::Record, Shared};
+
+#[cfg(feature = "journal-log")]
+use std::{fmt, sync::OnceLock};
+
+/// The level of a transition reported to a [Logger]. Acquisitions and releases are reported
+/// at [Level::Trace], and the rarer transitions, such as skipped tickets, completed cycles and
+/// poisoning, at [Level::Debug]. Only available with the `journal-log` feature.
+#[cfg(feature = "journal-log")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    /// Rare transitions of a sequence.
+    Debug,
+    /// Every turn taken.
+    Trace,
+}
+
+/// Receives a log record for each transition of every sequence, once installed with
+/// [set_logger]. Forward the records to the `log` crate by implementing [Logger::enabled]
+/// with `log::log_enabled!` and [Logger::log] with `log::log!`, under a target such as
+/// `"sequex"`, so that they can be toggled at runtime by log level. Only available with the
+/// `journal-log` feature.
+#[cfg(feature = "journal-log")]
+pub trait Logger: Send + Sync {
+    /// Returns true if records of `level` should be logged. Checked before each record is
+    /// formatted, so disabled levels cost little.
+    fn enabled(&self, level: Level) -> bool;
+
+    /// Log a record. Called on the thread making the transition, often while it holds or
+    /// hands on a lock, so it should return quickly and not panic.
+    fn log(&self, level: Level, message: fmt::Arguments<'_>);
+}
+
+#[cfg(feature = "journal-log")]
+static LOGGER: OnceLock<Box<dyn Logger>> = OnceLock::new();
+
+/// Install the logger that receives the transitions of every sequence. Returns the logger
+/// back if one was already installed. Only available with the `journal-log` feature.
+#[cfg(feature = "journal-log")]
+pub fn set_logger(logger: impl Logger + 'static) -> Result<(), Box<dyn Logger>> {
+    LOGGER.set(Box::new(logger))
+}
+
+#[cfg(feature = "journal-log")]
+fn log(level: Level, message: impl FnOnce(&dyn Logger, Level)) {
+    if let Some(logger) = LOGGER.get() {
+        if logger.enabled(level) {
+            message(&**logger, level);
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "journal-log"), allow(unused_variables))]
+impl<T> Shared<T> {
+    // Log a transition written to the journal.
+    pub(crate) fn log_record(&self, record: &Record) {
+        #[cfg(feature = "journal-log")]
+        {
+            let level = match record {
+                Record::Acquire { .. } | Record::Release { .. } | Record::Return { .. } => {
+                    Level::Trace
+                }
+                _ => Level::Debug,
+            };
+            let ticket = match record {
+                Record::Acquire { ticket, .. }
+                | Record::Release { ticket, .. }
+                | Record::Return { ticket } => Some(*ticket),
+                _ => None,
+            };
+            log(level, |logger, level| {
+                logger.log(
+                    level,
+                    format_args!("sequex {:p}: {record}{}", self, self.named(ticket)),
+                );
+            });
+        }
+    }
+
+    // Log the turn of a retired ticket being skipped.
+    pub(crate) fn log_skip(&self, ticket: u64) {
+        #[cfg(feature = "journal-log")]
+        log(Level::Debug, |logger, level| {
+            let cycle = self.cycle.load(std::sync::atomic::Ordering::SeqCst);
+            logger.log(
+                level,
+                format_args!(
+                    "sequex {:p}: skip {ticket} {cycle}{}",
+                    self,
+                    self.named(Some(ticket))
+                ),
+            );
+        });
+    }
+
+    // The name of a ticket to append to its log records.
+    #[cfg(feature = "journal-log")]
+    fn named(&self, ticket: Option<u64>) -> Named<'_> {
+        Named(ticket.and_then(|ticket| self.ticket_name(ticket)))
+    }
+}
+
+// Writes the name of a ticket in parentheses, if it has one.
+#[cfg(feature = "journal-log")]
+struct Named<'a>(Option<&'a str>);
+
+#[cfg(feature = "journal-log")]
+impl fmt::Display for Named<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(name) => write!(f, " ({name})"),
+            None => Ok(()),
+        }
+    }
+}
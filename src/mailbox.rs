@@ -0,0 +1,161 @@
+use crate::{try_boxed_slice, BuildError, Controller, Guard, Sequex, Shared};
+use std::{
+    any::Any,
+    collections::VecDeque,
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+// The messages addressed to each ticket, and those to be sent to every ticket at the end of
+// the current cycle. Nothing is delivered until a message is first sent.
+pub(crate) struct Mailboxes {
+    used: AtomicBool,
+    boxes: Box<[Mutex<Mailbox>]>,
+    broadcasts: Mutex<Vec<Broadcast>>,
+}
+
+// Makes a copy of a broadcast message for each ticket.
+type Broadcast = Box<dyn Fn() -> Box<dyn Any + Send> + Send>;
+
+#[derive(Default)]
+struct Mailbox {
+    pending: Vec<(u64, Box<dyn Any + Send>)>,
+    inbox: VecDeque<Box<dyn Any + Send>>,
+}
+
+impl<T> Sequex<T> {
+    /// Leave `message` for `ticket`, to be delivered when it next acquires the lock, where its
+    /// guard can take it with [Guard::take_mail]. Returns the message if there is no such
+    /// ticket.
+    pub fn send_to<M: Any + Send>(&self, ticket: u64, message: M) -> Result<(), M> {
+        self.shared.send_to(ticket, 0, message)
+    }
+
+    /// Leave `message` for `ticket`, to be delivered when it first acquires the lock in or
+    /// after `cycle`. Returns the message if there is no such ticket.
+    pub fn send_to_cycle<M: Any + Send>(
+        &self,
+        ticket: u64,
+        cycle: u64,
+        message: M,
+    ) -> Result<(), M> {
+        self.shared.send_to(ticket, cycle, message)
+    }
+
+    /// Send a copy of `message` to every ticket once the current cycle completes, delivered
+    /// like [Sequex::send_to] when each ticket first acquires the lock in the next cycle, so
+    /// stages can eg. reset their per-cycle state without tracking cycle numbers.
+    pub fn broadcast_at_cycle_end<M: Any + Clone + Send>(&self, message: M) {
+        self.shared.broadcast(message);
+    }
+}
+
+impl<T> Controller<T> {
+    /// Send a copy of `message` to every ticket once the current cycle completes. See
+    /// [Sequex::broadcast_at_cycle_end].
+    pub fn broadcast_at_cycle_end<M: Any + Clone + Send>(&self, message: M) {
+        self.shared.broadcast(message);
+    }
+
+    /// Leave `message` for `ticket`, to be delivered when it first acquires the lock in or
+    /// after `cycle`. See [Sequex::send_to_cycle].
+    pub fn send_to_cycle<M: Any + Send>(
+        &self,
+        ticket: u64,
+        cycle: u64,
+        message: M,
+    ) -> Result<(), M> {
+        self.shared.send_to(ticket, cycle, message)
+    }
+}
+
+impl<T> Guard<'_, T> {
+    /// Take the oldest message of type `M` delivered to this ticket. Messages that aren't
+    /// taken stay in the ticket's mailbox for its later turns.
+    pub fn take_mail<M: Any>(&mut self) -> Option<M> {
+        let mut mailbox = self.sequex.shared.mailboxes.get(self.sequex.ticket)?;
+        let index = mailbox.inbox.iter().position(|message| message.is::<M>())?;
+        let message = mailbox.inbox.remove(index)?;
+        message.downcast().ok().map(|message| *message)
+    }
+}
+
+impl Mailboxes {
+    pub(crate) fn new(len: usize) -> Result<Self, BuildError> {
+        Ok(Self {
+            used: AtomicBool::new(false),
+            boxes: try_boxed_slice(len, Mutex::default)?,
+            broadcasts: Mutex::default(),
+        })
+    }
+
+    fn get(&self, ticket: u64) -> Option<MutexGuard<'_, Mailbox>> {
+        let mailbox = self.boxes.get(ticket as usize)?;
+        Some(mailbox.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl<T> Shared<T> {
+    fn send_to<M: Any + Send>(&self, ticket: u64, cycle: u64, message: M) -> Result<(), M> {
+        let Some(mut mailbox) = self.mailboxes.get(ticket) else {
+            return Err(message);
+        };
+        mailbox.pending.push((cycle, Box::new(message)));
+        self.mailboxes.used.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn broadcast<M: Any + Clone + Send>(&self, message: M) {
+        self.mailboxes
+            .broadcasts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Box::new(move || Box::new(message.clone())));
+        self.mailboxes.used.store(true, Ordering::SeqCst);
+    }
+
+    // Send the broadcasts to every ticket, as the current cycle completes.
+    pub(crate) fn send_broadcasts(&self) {
+        if !self.mailboxes.used.load(Ordering::SeqCst) {
+            return;
+        }
+        let broadcasts = mem::take(
+            &mut *self
+                .mailboxes
+                .broadcasts
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        if broadcasts.is_empty() {
+            return;
+        }
+        let cycle = self.cycle.load(Ordering::SeqCst) + 1;
+        for mailbox in self.mailboxes.boxes.iter() {
+            let mut mailbox = mailbox.lock().unwrap_or_else(PoisonError::into_inner);
+            mailbox
+                .pending
+                .extend(broadcasts.iter().map(|broadcast| (cycle, broadcast())));
+        }
+    }
+
+    // Deliver the messages that are due to `ticket`, as its turn has just started.
+    pub(crate) fn deliver_mail(&self, ticket: u64) {
+        if !self.mailboxes.used.load(Ordering::SeqCst) {
+            return;
+        }
+        let Some(mut mailbox) = self.mailboxes.get(ticket) else {
+            return;
+        };
+        let cycle = self.cycle.load(Ordering::SeqCst);
+        let (due, pending) = mem::take(&mut mailbox.pending)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(at, _)| *at <= cycle);
+        mailbox.pending = pending;
+        mailbox
+            .inbox
+            .extend(due.into_iter().map(|(_, message)| message));
+    }
+}
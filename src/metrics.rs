@@ -0,0 +1,325 @@
+use crate::Shared;
+use std::time::Instant;
+
+#[cfg(feature = "metrics")]
+use std::{
+    fmt,
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError, Weak,
+    },
+    time::Duration,
+};
+
+/// Counters and histograms of the sequences they are attached to with
+/// [Builder::metrics](crate::Builder::metrics), which can be exported in the Prometheus text
+/// exposition format. Each sequence is labelled with its name, and sequences given the same
+/// name are exported as one series, adding up their metrics. A sequence is no longer exported
+/// once it has been dropped. Only available with the `metrics` feature.
+///
+/// The following metrics are exported, in seconds where they are durations:
+///
+/// - `sequex_turns_total`: the turns taken.
+/// - `sequex_cycles_total`: the cycles completed.
+/// - `sequex_poisoned_total`: the times the sequence was poisoned, at most once.
+/// - `sequex_wait_seconds`: a histogram of the time tickets spent blocked on their turn.
+/// - `sequex_hold_seconds`: a histogram of the time turns were held for.
+#[cfg(feature = "metrics")]
+#[derive(Clone, Default)]
+pub struct Metrics {
+    // Held weakly, so that the meters of dropped sequences can be pruned.
+    sequences: Arc<Mutex<Vec<Weak<Meter>>>>,
+}
+
+// The metrics of one sequence, and when the turn it is holding started.
+#[cfg(feature = "metrics")]
+pub(crate) struct Meter {
+    name: String,
+    turns: AtomicU64,
+    cycles: AtomicU64,
+    poisoned: AtomicU64,
+    wait: Histogram,
+    hold: Histogram,
+    held_since: Mutex<Option<Instant>>,
+}
+
+// The upper bounds of the histogram buckets, in seconds.
+#[cfg(feature = "metrics")]
+const BUCKETS: [f64; 8] = [1e-6, 1e-5, 1e-4, 1e-3, 1e-2, 1e-1, 1.0, 10.0];
+
+// A histogram of durations, with the count of observations in each bucket, not cumulative.
+#[cfg(feature = "metrics")]
+#[derive(Default)]
+struct Histogram {
+    buckets: [AtomicU64; BUCKETS.len() + 1],
+    sum_nanos: AtomicU64,
+}
+
+#[cfg(feature = "metrics")]
+impl Metrics {
+    /// Create an empty set of metrics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the metrics of every attached sequence in the Prometheus text exposition format.
+    pub fn encode_prometheus(&self, mut writer: impl Write) -> io::Result<()> {
+        let meters = self.live();
+        let meters = series(&meters);
+        let writer = &mut writer;
+        write_counter(
+            writer,
+            "sequex_turns_total",
+            "Turns taken.",
+            &meters,
+            |meter| &meter.turns,
+        )?;
+        write_counter(
+            writer,
+            "sequex_cycles_total",
+            "Cycles completed.",
+            &meters,
+            |meter| &meter.cycles,
+        )?;
+        write_counter(
+            writer,
+            "sequex_poisoned_total",
+            "Times the sequence was poisoned.",
+            &meters,
+            |meter| &meter.poisoned,
+        )?;
+        write_histogram(
+            writer,
+            "sequex_wait_seconds",
+            "Time tickets spent blocked on their turn.",
+            &meters,
+            |meter| &meter.wait,
+        )?;
+        write_histogram(
+            writer,
+            "sequex_hold_seconds",
+            "Time turns were held for.",
+            &meters,
+            |meter| &meter.hold,
+        )?;
+        writer.flush()
+    }
+
+    // Add a sequence to the metrics, returning the meter it records with.
+    pub(crate) fn meter(&self, name: String) -> Arc<Meter> {
+        let meter = Arc::new(Meter {
+            name,
+            turns: AtomicU64::new(0),
+            cycles: AtomicU64::new(0),
+            poisoned: AtomicU64::new(0),
+            wait: Histogram::default(),
+            hold: Histogram::default(),
+            held_since: Mutex::new(None),
+        });
+        let mut sequences = self.sequences();
+        sequences.retain(|meter| meter.strong_count() > 0);
+        sequences.push(Arc::downgrade(&meter));
+        meter
+    }
+
+    // Returns the meters of the sequences that are still alive, pruning the others.
+    fn live(&self) -> Vec<Arc<Meter>> {
+        let mut sequences = self.sequences();
+        sequences.retain(|meter| meter.strong_count() > 0);
+        sequences.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    fn sequences(&self) -> MutexGuard<'_, Vec<Weak<Meter>>> {
+        self.sequences
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// The meters of the sequences exported as one series, which share a name.
+#[cfg(feature = "metrics")]
+struct Series<'a> {
+    name: &'a str,
+    meters: Vec<&'a Meter>,
+}
+
+// Group `meters` into series by name, in the order the names were first attached.
+#[cfg(feature = "metrics")]
+fn series(meters: &[Arc<Meter>]) -> Vec<Series<'_>> {
+    let mut series: Vec<Series<'_>> = Vec::new();
+    for meter in meters {
+        match series.iter_mut().find(|series| series.name == meter.name) {
+            Some(series) => series.meters.push(meter),
+            None => series.push(Series {
+                name: &meter.name,
+                meters: vec![meter],
+            }),
+        }
+    }
+    series
+}
+
+#[cfg(feature = "metrics")]
+impl Meter {
+    fn held_since(&self) -> MutexGuard<'_, Option<Instant>> {
+        self.held_since
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl Histogram {
+    fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let index = BUCKETS
+            .iter()
+            .position(|le| seconds <= *le)
+            .unwrap_or(BUCKETS.len());
+        if let Some(bucket) = self.buckets.get(index) {
+            bucket.fetch_add(1, Ordering::Relaxed);
+        }
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        self.sum_nanos.fetch_add(nanos, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn write_counter(
+    writer: &mut impl Write,
+    name: &str,
+    help: &str,
+    meters: &[Series<'_>],
+    counter: impl Fn(&Meter) -> &AtomicU64,
+) -> io::Result<()> {
+    writeln!(writer, "# HELP {name} {help}")?;
+    writeln!(writer, "# TYPE {name} counter")?;
+    for series in meters {
+        let label = Label(series.name);
+        let value: u64 = series
+            .meters
+            .iter()
+            .map(|meter| counter(meter).load(Ordering::Relaxed))
+            .sum();
+        writeln!(writer, "{name}{{sequence=\"{label}\"}} {value}")?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "metrics")]
+fn write_histogram(
+    writer: &mut impl Write,
+    name: &str,
+    help: &str,
+    meters: &[Series<'_>],
+    histogram: impl Fn(&Meter) -> &Histogram,
+) -> io::Result<()> {
+    writeln!(writer, "# HELP {name} {help}")?;
+    writeln!(writer, "# TYPE {name} histogram")?;
+    for series in meters {
+        let label = Label(series.name);
+        let histograms = || series.meters.iter().map(|meter| histogram(meter));
+        // The exposition format counts the observations up to each bound.
+        let mut count = 0;
+        for index in 0..=BUCKETS.len() {
+            count += histograms()
+                .filter_map(|histogram| histogram.buckets.get(index))
+                .map(|bucket| bucket.load(Ordering::Relaxed))
+                .sum::<u64>();
+            match BUCKETS.get(index) {
+                Some(le) => writeln!(
+                    writer,
+                    "{name}_bucket{{sequence=\"{label}\",le=\"{le}\"}} {count}"
+                )?,
+                None => writeln!(
+                    writer,
+                    "{name}_bucket{{sequence=\"{label}\",le=\"+Inf\"}} {count}"
+                )?,
+            }
+        }
+        let sum = histograms()
+            .map(|histogram| histogram.sum_nanos.load(Ordering::Relaxed))
+            .fold(0u64, u64::saturating_add) as f64
+            / 1e9;
+        writeln!(writer, "{name}_sum{{sequence=\"{label}\"}} {sum}")?;
+        writeln!(writer, "{name}_count{{sequence=\"{label}\"}} {count}")?;
+    }
+    Ok(())
+}
+
+// Escapes a string for use as a Prometheus label value.
+#[cfg(feature = "metrics")]
+struct Label<'a>(&'a str);
+
+#[cfg(feature = "metrics")]
+impl fmt::Display for Label<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '\\' => write!(f, "\\\\")?,
+                '"' => write!(f, "\\\"")?,
+                '\n' => write!(f, "\\n")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+impl<T> Shared<T> {
+    // Returns true if the sequence keeps metrics of the time spent waiting.
+    pub(crate) fn measures_waits(&self) -> bool {
+        #[cfg(feature = "metrics")]
+        if self.config.meter.is_some() {
+            return true;
+        }
+        false
+    }
+
+    // Record the time a ticket spent waiting since `since` for the turn it has just acquired,
+    // which ends as the turn starts.
+    pub(crate) fn measure_wait(&self, since: Option<Instant>) {
+        #[cfg(feature = "metrics")]
+        if let (Some(meter), Some(since)) = (&self.config.meter, since) {
+            let end = meter.held_since().unwrap_or_else(Instant::now);
+            meter.wait.observe(end.saturating_duration_since(since));
+        }
+    }
+
+    // Start timing the turn that has just been acquired.
+    pub(crate) fn measure_acquire(&self) {
+        #[cfg(feature = "metrics")]
+        if let Some(meter) = &self.config.meter {
+            meter.turns.fetch_add(1, Ordering::Relaxed);
+            *meter.held_since() = Some(Instant::now());
+        }
+    }
+
+    // Record the held turn as it ends.
+    pub(crate) fn measure_release(&self) {
+        #[cfg(feature = "metrics")]
+        if let Some(meter) = &self.config.meter {
+            if let Some(since) = meter.held_since().take() {
+                meter.hold.observe(since.elapsed());
+            }
+        }
+    }
+
+    // Count a completed cycle.
+    pub(crate) fn measure_cycle(&self) {
+        #[cfg(feature = "metrics")]
+        if let Some(meter) = &self.config.meter {
+            meter.cycles.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    // Count the sequence being poisoned.
+    pub(crate) fn measure_poison(&self) {
+        #[cfg(feature = "metrics")]
+        if let Some(meter) = &self.config.meter {
+            meter.poisoned.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{is_live, PoisonCause, PoisonReason, Shared};
+use std::{
+    mem,
+    sync::{atomic::Ordering, Mutex, MutexGuard, PoisonError},
+    thread::{self, ThreadId},
+};
+
+// The thread and ticket holding the lock, and the cycle the turn was acquired in, recorded in
+// debug builds to catch misuse that would otherwise deadlock or poison the sequence. A guard
+// that is sent to another thread is still attributed to the thread that acquired it until it
+// is dropped.
+#[derive(Default)]
+pub(crate) struct LockedBy {
+    holder: Mutex<Option<Locker>>,
+}
+
+#[derive(Clone, Copy)]
+struct Locker {
+    thread: ThreadId,
+    ticket: u64,
+    cycle: u64,
+}
+
+impl LockedBy {
+    fn get(&self) -> MutexGuard<'_, Option<Locker>> {
+        self.holder.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Returns the ticket whose lock is held by the current thread, if any.
+    fn held_here(&self) -> Option<u64> {
+        let holder = *self.get();
+        holder
+            .filter(|holder| holder.thread == thread::current().id())
+            .map(|holder| holder.ticket)
+    }
+}
+
+impl<T> Shared<T> {
+    // Record that `ticket` has acquired the lock on the current thread, or that the lock was
+    // released, in debug builds. A release checks that the turn didn't span a cycle.
+    pub(crate) fn set_locked_by(&self, ticket: Option<u64>) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        let cycle = self.cycle.load(Ordering::SeqCst);
+        let locker = ticket.map(|ticket| Locker {
+            thread: thread::current().id(),
+            ticket,
+            cycle,
+        });
+        let released = mem::replace(&mut *self.locked_by.get(), locker);
+        if ticket.is_none() {
+            if let Some(released) = released {
+                self.check_cycle(released, cycle);
+            }
+        }
+    }
+
+    // Panic in debug builds if the cycle advanced past the one the turn of `locker` was
+    // acquired in, as the turn can't pass on while its guard is held. Guards held across a
+    // full cycle mean the state of the sequence was corrupted, so it is poisoned first for
+    // the other tickets to notice.
+    #[allow(clippy::panic)]
+    fn check_cycle(&self, locker: Locker, cycle: u64) {
+        if locker.cycle == cycle || thread::panicking() {
+            return;
+        }
+        self.poison(PoisonCause::new(
+            Some(locker.ticket),
+            PoisonReason::Corrupted,
+        ));
+        panic!(
+            "the guard of ticket {} of a sequex was held from cycle {} to cycle {cycle}: the \
+             turn passed on while its guard was still held, so the state of the sequence was \
+             corrupted.",
+            locker.ticket, locker.cycle
+        );
+    }
+
+    // Panic in debug builds if `ticket` is about to block on its turn while the current thread
+    // holds the lock of the same sequence, which would never be released, or while a guard
+    // has been held across a cycle.
+    #[allow(clippy::panic)]
+    pub(crate) fn check_lock(&self, ticket: u64) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        // The cycle is read while the record is locked, as the holder clears the record
+        // before its release advances the cycle.
+        let held = {
+            let holder = self.locked_by.get();
+            (*holder).map(|holder| (holder, self.cycle.load(Ordering::SeqCst)))
+        };
+        if let Some((holder, cycle)) = held {
+            self.check_cycle(holder, cycle);
+        }
+        match self.locked_by.held_here() {
+            Some(held) if held == ticket => panic!(
+                "ticket {ticket} of a sequex was locked again while its guard is still held on \
+                 this thread: its next turn is a full cycle away, which can't complete until \
+                 the guard is dropped. Drop the guard before locking the ticket again."
+            ),
+            Some(held) => panic!(
+                "ticket {ticket} of a sequex was locked while this thread holds the guard of \
+                 ticket {held} of the same sequence, which would deadlock: the turn can't \
+                 advance until that guard is dropped. Drop the guard of ticket {held} first, or \
+                 lock ticket {ticket} on another thread."
+            ),
+            None => {}
+        }
+    }
+
+    // Panic in debug builds if `ticket` is dropped while the current thread holds the lock of
+    // the same sequence, which poisons the sequence under the turn that is being held.
+    #[allow(clippy::panic)]
+    pub(crate) fn check_drop(&self, ticket: u64) {
+        if !cfg!(debug_assertions) || !self.config.poisoning || thread::panicking() {
+            return;
+        }
+        if let Some(held) = self.locked_by.held_here() {
+            panic!(
+                "ticket {ticket} of a sequex was dropped while this thread holds the guard of \
+                 ticket {held}, which poisons the sequence. Drop the guard first, or build the \
+                 sequence with `Builder::poisoning(false)` to let the other tickets skip the \
+                 dropped one."
+            );
+        }
+    }
+
+    // Handle the state word having changed under the holder of the lock as it hands the
+    // turn on. Poisoning or ending the sequence in the meantime is expected, but finding the
+    // turn of a ticket means the state was corrupted, which panics in debug builds and
+    // poisons the sequence otherwise, rather than passing unnoticed.
+    #[allow(clippy::panic)]
+    pub(crate) fn lost_handoff(&self, ticket: u64, actual: u64) {
+        if !is_live(actual) {
+            return;
+        }
+        if cfg!(debug_assertions) && !thread::panicking() {
+            panic!(
+                "the turn of ticket {ticket} of a sequex was handed to ticket {actual} while \
+                 it still held the lock: the state of the sequence was corrupted."
+            );
+        }
+        self.poison(PoisonCause::new(Some(ticket), PoisonReason::Corrupted));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{wait::block, Guard, Sequex, SequexPoisoned, WaitStrategy};
+
+/// A tuple of tickets, possibly on different sequences, that can be locked together with
+/// [lock_all].
+pub trait LockAll<'a> {
+    /// The guards returned once every ticket was locked.
+    type Guards;
+
+    /// Attempt to lock every ticket at once. Does not block the current thread. The turns
+    /// are only taken once it is every ticket's turn, so a ticket that isn't ready leaves the
+    /// others untouched. If a turn is lost to a race in between, such as with another member
+    /// of a ticket group, the turns that were acquired are handed back without advancing
+    /// their sequences. Returns [SequexPoisoned] if any lock was poisoned.
+    fn try_lock_all(&self) -> Result<Option<Self::Guards>, SequexPoisoned>;
+}
+
+/// Acquire the turns of several tickets, blocking the current thread until all of them could
+/// be locked at the same time. Turns are never held while waiting on another one, so
+/// acquisitions on multiple sequences cannot deadlock each other. Returns [SequexPoisoned] if
+/// any of the locks was poisoned.
+///
+/// Passing the same ticket twice will never succeed.
+pub fn lock_all<'a, L: LockAll<'a>>(tickets: L) -> Result<L::Guards, SequexPoisoned> {
+    block(WaitStrategy::default(), None, None, || {
+        tickets.try_lock_all()
+    })
+}
+
+macro_rules! impl_lock_all {
+    ($($name:ident: $t:ident),+) => {
+        impl<'a, $($t),+> LockAll<'a> for ($(&'a Sequex<$t>,)+) {
+            type Guards = ($(Guard<'a, $t>,)+);
+
+            fn try_lock_all(&self) -> Result<Option<Self::Guards>, SequexPoisoned> {
+                let ($($name,)+) = *self;
+                // Taking a turn has side effects, such as receiving the baton and recording
+                // the turn, so every ticket is checked first and the turns are only taken
+                // once all of them are ready.
+                let mut poisoned = false;
+                let mut ready = true;
+                $(
+                    match $name.ready() {
+                        Ok(true) => (),
+                        Ok(false) => ready = false,
+                        Err(SequexPoisoned) => poisoned = true,
+                    }
+                )+
+                if poisoned {
+                    return Err(SequexPoisoned);
+                }
+                if !ready {
+                    return Ok(None);
+                }
+                match ($($name.try_lock(),)+) {
+                    ($(Ok(Some($name)),)+) => Ok(Some(($($name,)+))),
+                    ($($name,)+) => {
+                        let mut poisoned = false;
+                        $(
+                            match $name {
+                                Ok(Some(guard)) => guard.give_back(),
+                                Ok(None) => (),
+                                Err(SequexPoisoned) => poisoned = true,
+                            }
+                        )+
+                        if poisoned {
+                            Err(SequexPoisoned)
+                        } else {
+                            Ok(None)
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_lock_all!(a: A);
+impl_lock_all!(a: A, b: B);
+impl_lock_all!(a: A, b: B, c: C);
+impl_lock_all!(a: A, b: B, c: C, d: D);
+impl_lock_all!(a: A, b: B, c: C, d: D, e: E);
+impl_lock_all!(a: A, b: B, c: C, d: D, e: E, f: F);
+impl_lock_all!(a: A, b: B, c: C, d: D, e: E, f: F, g: G);
+impl_lock_all!(a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H);
@@ -0,0 +1,59 @@
+use crate::{BuildError, Sequex, Shared};
+
+#[cfg(feature = "sync")]
+use crate::Controller;
+
+impl<T> Sequex<T> {
+    /// Returns the name this ticket was given when the sequence was built, if any. See
+    /// [Builder::ticket](crate::Builder::ticket).
+    pub fn name(&self) -> Option<&str> {
+        self.shared.ticket_name(self.ticket)
+    }
+
+    /// Returns the number of the ticket that was named `name` when the sequence was built, if
+    /// any, so that stages can be found by name. See [Builder::ticket](crate::Builder::ticket).
+    pub fn ticket_named(&self, name: &str) -> Option<u64> {
+        self.shared.ticket_named(name)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<T> Controller<T> {
+    /// Returns the name `ticket` was given when the sequence was built, if any. See
+    /// [Builder::ticket](crate::Builder::ticket).
+    pub fn ticket_name(&self, ticket: u64) -> Option<&str> {
+        self.shared.ticket_name(ticket)
+    }
+
+    /// Returns the number of the ticket that was named `name` when the sequence was built, if
+    /// any, so that stages can be found by name. See [Builder::ticket](crate::Builder::ticket).
+    pub fn ticket_named(&self, name: &str) -> Option<u64> {
+        self.shared.ticket_named(name)
+    }
+}
+
+// Check the names given to the tickets of a sequence of `num_tickets`.
+pub(crate) fn check(names: &[String], num_tickets: u64) -> Result<(), BuildError> {
+    if names.len() as u64 > num_tickets {
+        return Err(BuildError::TooManyNames);
+    }
+    for (i, name) in names.iter().enumerate() {
+        if names.iter().skip(i + 1).any(|other| other == name) {
+            return Err(BuildError::DuplicateName);
+        }
+    }
+    Ok(())
+}
+
+impl<T> Shared<T> {
+    pub(crate) fn ticket_name(&self, ticket: u64) -> Option<&str> {
+        self.config.names.get(ticket as usize).map(String::as_str)
+    }
+
+    pub(crate) fn ticket_named(&self, name: &str) -> Option<u64> {
+        (0..)
+            .zip(&self.config.names)
+            .find(|(_, named)| *named == name)
+            .map(|(ticket, _)| ticket)
+    }
+}
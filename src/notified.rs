@@ -0,0 +1,133 @@
+use crate::{is_live, Delay, Guard, Sequex, SequexPoisoned, ThreadDelay};
+use std::{
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::atomic::Ordering,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+/// A future that resolves once it is a ticket's turn, without locking. Created with
+/// [Sequex::notified].
+pub struct Notified<'a, T> {
+    sequex: &'a Sequex<T>,
+}
+
+// Wakes a task once the pacing or schedule delay of its turn has passed. Futures keep one
+// across polls, so that a single timer is running for them however often they are polled.
+#[derive(Default)]
+pub(crate) struct TurnTimer(Option<ThreadDelay>);
+
+impl<T> Sequex<T> {
+    /// Wait until it is this ticket's turn, without acquiring the lock, so that async tasks
+    /// can select between their turn and other events. The future resolves with
+    /// [SequexPoisoned] if the lock was poisoned or the sequence has finished. The turn may
+    /// have been taken by a snapshot by the time the task locks, so [Sequex::try_lock] can
+    /// still fail after it resolves.
+    pub fn notified(&self) -> Notified<'_, T> {
+        Notified { sequex: self }
+    }
+
+    /// Wait for this ticket's turn without blocking the thread, and lock it. Returns
+    /// [SequexPoisoned] if the lock was poisoned or the sequence has finished.
+    ///
+    /// Tickets of one sequence may be split between async tasks using this and threads using
+    /// [Sequex::lock], such as stages on a runtime feeding dedicated worker threads. Each
+    /// release wakes both the threads and the tasks waiting for the next turn, so neither
+    /// kind of waiter needs to know about the other.
+    pub async fn lock_async(&self) -> Result<Guard<'_, T>, SequexPoisoned> {
+        let wait = self
+            .shared
+            .instrument_wait(self.ticket, "Sequex::lock_async");
+        let mut timer = TurnTimer::default();
+        poll_fn(|cx| {
+            let poll = self.poll_lock(cx, &mut timer);
+            if let Some(wait) = &wait {
+                wait.polled(poll.is_ready());
+            }
+            poll
+        })
+        .await
+    }
+
+    /// Wait for this ticket's turn, lock it and run `f` on the value, releasing the turn when
+    /// `f` completes. The turn is held across the await points of `f`, and is also released if
+    /// the future is dropped before it completes, so holders never keep a [Guard] alive across
+    /// awaits themselves. Returns [SequexPoisoned] if the lock was poisoned or the sequence has
+    /// finished.
+    pub async fn lock_and<R>(&self, f: impl AsyncFnOnce(&mut T) -> R) -> Result<R, SequexPoisoned> {
+        let mut guard = self.lock_async().await?;
+        Ok(f(&mut guard).await)
+    }
+
+    // Attempt to acquire the lock, registering `cx` to be woken when this ticket's turn comes
+    // if it is not ready, with `timer` if the turn is delayed.
+    pub(crate) fn poll_lock(
+        &self,
+        cx: &mut Context<'_>,
+        timer: &mut TurnTimer,
+    ) -> Poll<Result<Guard<'_, T>, SequexPoisoned>> {
+        if let Some(guard) = self.try_lock()? {
+            return Poll::Ready(Ok(guard));
+        }
+        if let Some(remaining) = self.shared.start_delay(self.ticket) {
+            // Nothing else wakes the task once the interval has passed.
+            timer.wake_after(remaining, cx);
+        }
+        if let Some(waiters) = self.shared.turn_waiters.get(self.ticket as usize) {
+            waiters.register(cx.waker());
+        }
+        match self.try_lock()? {
+            Some(guard) => Poll::Ready(Ok(guard)),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl TurnTimer {
+    // Wake the task of `cx` once `remaining` has passed. A timer that hasn't fired yet is
+    // kept, as the delays of a turn only ever move later, and the task polls again when it
+    // fires.
+    fn wake_after(&mut self, remaining: Duration, cx: &mut Context<'_>) {
+        let running = self
+            .0
+            .as_ref()
+            .and_then(ThreadDelay::deadline)
+            .is_some_and(|deadline| deadline > Instant::now());
+        if !running {
+            self.0 = Some(ThreadDelay::delay(remaining));
+        }
+        if let Some(delay) = &mut self.0 {
+            // The delay only registers the waker here, as it hasn't passed.
+            let _ = Pin::new(delay).poll(cx);
+        }
+    }
+}
+
+impl<T> Notified<'_, T> {
+    fn check(&self) -> Poll<Result<(), SequexPoisoned>> {
+        match self.sequex.shared.current.load(Ordering::SeqCst) {
+            current if current == self.sequex.ticket => Poll::Ready(Ok(())),
+            current if !is_live(current) => Poll::Ready(Err(SequexPoisoned)),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl<T> Future for Notified<'_, T> {
+    type Output = Result<(), SequexPoisoned>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(result) = self.check() {
+            return Poll::Ready(result);
+        }
+        if let Some(waiters) = self
+            .sequex
+            .shared
+            .turn_waiters
+            .get(self.sequex.ticket as usize)
+        {
+            waiters.register(cx.waker());
+        }
+        self.check()
+    }
+}
@@ -0,0 +1,108 @@
+use crate::Shared;
+use std::mem;
+
+impl<T> Shared<T> {
+    // Bind the pages of the ticket word to the configured NUMA node, once the shared state is
+    // in its final place.
+    pub(crate) fn place(&self) {
+        if let Some(node) = self.config.numa_node {
+            let current = &self.current;
+            mbind::prefer(
+                (current as *const _ as *const u8).cast_mut(),
+                mem::size_of_val(current),
+                node,
+            );
+        }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+))]
+mod mbind {
+    use std::{
+        ffi::{c_int, c_long, c_uint, c_ulong},
+        fs,
+    };
+
+    extern "C" {
+        fn syscall(num: c_long, ...) -> c_long;
+        fn sysconf(name: c_int) -> c_long;
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_MBIND: c_long = 237;
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+    const SYS_MBIND: c_long = 235;
+
+    const SC_PAGESIZE: c_int = 30;
+    const MPOL_PREFERRED: c_int = 1;
+    const MPOL_MF_MOVE: c_uint = 1 << 1;
+
+    // The most nodes a kernel can be built with.
+    const MAX_NODES: u32 = 1 << 10;
+
+    // Returns whether `node` is one the kernel could ever bring online, going by the ranges
+    // listed in `/sys/devices/system/node/possible`, eg. `0-3` or `0,2-5`.
+    fn possible(node: u32) -> bool {
+        let Ok(ranges) = fs::read_to_string("/sys/devices/system/node/possible") else {
+            return false;
+        };
+        let last = ranges
+            .trim()
+            .rsplit([',', '-'])
+            .next()
+            .and_then(|last| last.parse::<u32>().ok());
+        matches!(last, Some(last) if node <= last && node < MAX_NODES)
+    }
+
+    // Prefer `node` for the pages spanning `len` bytes at `addr`. Failures are ignored, as
+    // placement is only a hint.
+    pub(crate) fn prefer(addr: *mut u8, len: usize, node: u32) {
+        if !possible(node) {
+            return;
+        }
+        let Ok(page) = usize::try_from(unsafe { sysconf(SC_PAGESIZE) }) else {
+            return;
+        };
+        if page == 0 {
+            return;
+        }
+        let start = addr as usize / page * page;
+        let end = (addr as usize + len).next_multiple_of(page);
+        // The kernel reads one bit fewer than the length of the mask it is given.
+        let bits = c_ulong::BITS as usize;
+        let mut mask = vec![0 as c_ulong; node as usize / bits + 1];
+        if let Some(word) = mask.get_mut(node as usize / bits) {
+            *word = 1 << (node as usize % bits);
+        }
+        unsafe {
+            syscall(
+                SYS_MBIND,
+                start,
+                end - start,
+                MPOL_PREFERRED,
+                mask.as_ptr(),
+                mask.len() * bits + 1,
+                MPOL_MF_MOVE,
+            );
+        }
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+)))]
+mod mbind {
+    pub(crate) fn prefer(_addr: *mut u8, _len: usize, _node: u32) {}
+}
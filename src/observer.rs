@@ -0,0 +1,114 @@
+use crate::{CompletionHandle, Event, Holder, Sequex, SequexPoisoned, Shared};
+use std::sync::{Arc, Weak};
+
+/// A handle to a sequence that does not own a ticket, for code that monitors a sequence
+/// without taking part in it. Keeps the shared state alive, see [WeakSequex] for a handle
+/// that doesn't.
+pub struct Observer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// A weak handle to a sequence that does not keep its shared state alive, so long-lived
+/// monitoring code doesn't prevent finished sequences from being torn down. Created with
+/// [Sequex::downgrade] or [Observer::downgrade].
+pub struct WeakSequex<T> {
+    shared: Weak<Shared<T>>,
+}
+
+impl<T> Sequex<T> {
+    /// Create an observer of this sequence.
+    pub fn observer(&self) -> Observer<T> {
+        Observer {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Create a weak handle to this sequence.
+    pub fn downgrade(&self) -> WeakSequex<T> {
+        WeakSequex {
+            shared: Arc::downgrade(&self.shared),
+        }
+    }
+}
+
+impl<T> Observer<T> {
+    /// Create a weak handle to the observed sequence.
+    pub fn downgrade(&self) -> WeakSequex<T> {
+        WeakSequex {
+            shared: Arc::downgrade(&self.shared),
+        }
+    }
+
+    /// Returns the number of tickets the sequence was created with.
+    pub fn num_tickets(&self) -> u64 {
+        self.shared.num_tickets
+    }
+
+    /// Returns the ticket and thread that hold the lock. See [Sequex::holder].
+    pub fn holder(&self) -> Option<Holder> {
+        self.shared.holder()
+    }
+
+    /// Returns the number of ticket handles of the sequence that are still alive.
+    pub fn ticket_count(&self) -> u64 {
+        self.shared.ticket_count()
+    }
+
+    /// Returns the positions of the tickets whose handles have been dropped, in order.
+    pub fn retired_tickets(&self) -> Vec<u64> {
+        self.shared.retired_tickets()
+    }
+
+    /// Returns true if a one-shot sequence has completed its only cycle.
+    pub fn is_complete(&self) -> bool {
+        self.shared.is_complete()
+    }
+
+    /// Returns the number of turns that remain until the current cycle completes. See
+    /// [Sequex::remaining_turns].
+    pub fn remaining_turns(&self) -> u64 {
+        self.shared.remaining_turns()
+    }
+
+    /// Clone the current value without taking a turn. See [Sequex::snapshot].
+    pub fn snapshot(&self) -> Result<T, SequexPoisoned>
+    where
+        T: Clone,
+    {
+        self.shared.snapshot()
+    }
+
+    /// Create a handle that resolves once the current cycle has completed. See
+    /// [Sequex::completion].
+    pub fn completion(&self) -> CompletionHandle<T> {
+        CompletionHandle::new(&self.shared)
+    }
+
+    /// Register a callback for transitions of the sequence. See [Sequex::on_event].
+    pub fn on_event(&self, callback: impl Fn(Event) + Send + Sync + 'static) {
+        self.shared.callbacks.register(Arc::new(callback));
+    }
+}
+
+impl<T> Clone for Observer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> WeakSequex<T> {
+    /// Upgrade to an observer, if the shared state of the sequence still exists.
+    pub fn upgrade(&self) -> Option<Observer<T>> {
+        self.shared.upgrade().map(|shared| Observer { shared })
+    }
+}
+
+impl<T> Clone for WeakSequex<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::Shared;
+use std::{
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+// The shortest time between the starts of two consecutive turns, and the earliest the next
+// turn may start.
+pub(crate) struct Pacing {
+    interval: Duration,
+    next_start: Mutex<Option<Instant>>,
+}
+
+impl Pacing {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_start: Mutex::new(None),
+        }
+    }
+
+    // The same pacing, for a successor of the sequence.
+    pub(crate) fn renewed(&self) -> Self {
+        Self::new(self.interval)
+    }
+}
+
+impl<T> Shared<T> {
+    // Returns how long until the next turn may start, if the sequence is paced and it can't
+    // start yet.
+    pub(crate) fn pace_remaining(&self) -> Option<Duration> {
+        let pacing = self.config.pacing.as_ref()?;
+        let next_start = (*pacing
+            .next_start
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner))?;
+        let remaining = next_start.saturating_duration_since(Instant::now());
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    // Hold off the next turn for the interval, as a turn has just started.
+    pub(crate) fn start_pace(&self) {
+        if let Some(pacing) = &self.config.pacing {
+            let next_start = Instant::now().checked_add(pacing.interval);
+            *pacing
+                .next_start
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = next_start;
+        }
+    }
+}
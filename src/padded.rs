@@ -0,0 +1,21 @@
+use std::ops::{Deref, DerefMut};
+
+// A value aligned to its own pair of cache lines, so that threads spinning on it don't
+// contend with writes to the fields around it. Two lines are covered as adjacent line
+// prefetching pulls them in together on x86-64, and Apple silicon has 128 byte lines.
+#[repr(align(128))]
+#[derive(Default)]
+pub(crate) struct CachePadded<T>(pub(crate) T);
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for CachePadded<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
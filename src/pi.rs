@@ -0,0 +1,288 @@
+use crate::{Guard, Sequex, SequexPoisoned, Shared, Status, LOCKED};
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::atomic::Ordering,
+};
+
+/// A ticket of a sequence whose lock is backed by a priority-inheriting futex, created with
+/// [Builder::build_priority_inheriting](crate::Builder::build_priority_inheriting). The
+/// futex is owned by the thread that took the turn, so its guards can't be sent to another
+/// thread, and the ticket only offers the operations that release a turn on the thread that
+/// acquired it.
+pub struct PiSequex<T> {
+    sequex: Sequex<T>,
+}
+
+/// An RAII guard of a [PiSequex] ticket, which releases the lock when dropped. It can't be
+/// sent to another thread, since only the thread that locked the futex may unlock it.
+///
+/// ```compile_fail
+/// fn send<T: Send>(_: T) {}
+///
+/// let tickets = sequex::Sequex::builder(0u32, 1).build_priority_inheriting();
+/// send(tickets[0].lock().unwrap());
+/// ```
+pub struct PiGuard<'a, T> {
+    guard: Guard<'a, T>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<T> PiSequex<T> {
+    pub(crate) fn new(sequex: Sequex<T>) -> Self {
+        Self { sequex }
+    }
+
+    /// Returns the position of this ticket in the sequence.
+    pub fn ticket(&self) -> u64 {
+        self.sequex.ticket()
+    }
+
+    /// Attempt to acquire the lock, without blocking the current thread. See
+    /// [Sequex::try_lock].
+    pub fn try_lock(&self) -> Result<Option<PiGuard<'_, T>>, SequexPoisoned> {
+        Ok(self.sequex.try_lock()?.map(PiGuard::new))
+    }
+
+    /// Acquire the lock, blocking the current thread until it is this ticket's turn, and
+    /// boosting the priority of the ticket holding the lock in the meantime. See
+    /// [Sequex::lock].
+    pub fn lock(&self) -> Result<PiGuard<'_, T>, SequexPoisoned> {
+        self.sequex.lock().map(PiGuard::new)
+    }
+
+    /// Acquire the lock and run `f` on the value, releasing the lock once it returns. See
+    /// [Sequex::lock_with].
+    pub fn lock_with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R, SequexPoisoned> {
+        self.sequex.lock_with(f)
+    }
+
+    /// Wait for this ticket's turn and pass it to the next ticket. See [Sequex::pass].
+    pub fn pass(&self) -> Result<(), SequexPoisoned> {
+        self.sequex.pass()
+    }
+
+    /// Returns the state of the sequence as seen by this ticket. See [Sequex::status].
+    pub fn status(&self) -> Status {
+        self.sequex.status()
+    }
+
+    /// Poison the sequence. See [Sequex::poison].
+    pub fn poison(&self) {
+        self.sequex.poison()
+    }
+}
+
+impl<'a, T> PiGuard<'a, T> {
+    fn new(guard: Guard<'a, T>) -> Self {
+        Self {
+            guard,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the cycle this turn belongs to. See [Guard::cycle].
+    pub fn cycle(&self) -> u64 {
+        self.guard.cycle()
+    }
+}
+
+impl<T> Deref for PiGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for PiGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+// A priority-inheriting lock held alongside a turn, so that threads waiting for a turn boost
+// the priority of the thread currently holding the lock. Backed by a PI futex on Linux, and
+// a no-op everywhere else.
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+))]
+mod futex {
+    use crate::wait::MAX_BACKOFF;
+    use std::{
+        ffi::{c_int, c_long},
+        ptr,
+        sync::atomic::{AtomicBool, AtomicU32, Ordering},
+        time::{Duration, SystemTime},
+    };
+
+    extern "C" {
+        fn syscall(num: c_long, ...) -> c_long;
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_FUTEX: c_long = 202;
+    #[cfg(target_arch = "x86_64")]
+    const SYS_GETTID: c_long = 186;
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+    const SYS_FUTEX: c_long = 98;
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+    const SYS_GETTID: c_long = 178;
+
+    const FUTEX_LOCK_PI: c_int = 6;
+    const FUTEX_UNLOCK_PI: c_int = 7;
+    const FUTEX_PRIVATE_FLAG: c_int = 128;
+
+    #[repr(C)]
+    struct Timespec {
+        tv_sec: c_long,
+        tv_nsec: c_long,
+    }
+
+    thread_local! {
+        static TID: u32 = unsafe { syscall(SYS_GETTID) as u32 };
+    }
+
+    #[derive(Default)]
+    pub(crate) struct PiLock {
+        word: AtomicU32,
+        // Whether the holder of the turn holds the futex too, and whether unlocking it failed,
+        // after which it is left alone rather than blocking on an owner that will never
+        // unlock it.
+        held: AtomicBool,
+        broken: AtomicBool,
+    }
+
+    impl PiLock {
+        // Lock the futex for the turn that has just been acquired.
+        pub(crate) fn acquire(&self) {
+            self.held.store(self.lock(None), Ordering::SeqCst);
+        }
+
+        // Unlock the futex as the turn is released, if the turn locked it.
+        pub(crate) fn release(&self) {
+            if self.held.swap(false, Ordering::SeqCst) && !self.unlock() {
+                self.broken.store(true, Ordering::SeqCst);
+                debug_assert!(false, "a priority-inheriting futex could not be unlocked");
+            }
+        }
+
+        // Block until the holder of the futex unlocks it, lending it our priority meanwhile.
+        // Gives up after as long as a backoff wait parks at most, so that the caller can
+        // check for an interrupt.
+        pub(crate) fn wait(&self) {
+            if self.lock(Some(Duration::from_micros(MAX_BACKOFF))) && !self.unlock() {
+                self.broken.store(true, Ordering::SeqCst);
+            }
+        }
+
+        // Lock the futex, blocking in the kernel for up to `timeout` if it is contended.
+        // Waiters blocked here lend their priority to the owner. Returns false if the futex
+        // couldn't be locked.
+        fn lock(&self, timeout: Option<Duration>) -> bool {
+            if self.broken.load(Ordering::SeqCst) {
+                return false;
+            }
+            let tid = TID.with(|tid| *tid);
+            if self
+                .word
+                .compare_exchange(0, tid, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                return true;
+            }
+            // The kernel takes the timeout of FUTEX_LOCK_PI as a deadline on the realtime
+            // clock.
+            let deadline = timeout.map(|timeout| {
+                let deadline = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    + timeout;
+                Timespec {
+                    tv_sec: deadline.as_secs() as c_long,
+                    tv_nsec: deadline.subsec_nanos() as c_long,
+                }
+            });
+            self.futex(FUTEX_LOCK_PI, deadline.as_ref())
+        }
+
+        // Unlock the futex, which must be held by the current thread. Returns false if the
+        // kernel refused, because it isn't.
+        fn unlock(&self) -> bool {
+            let tid = TID.with(|tid| *tid);
+            self.word
+                .compare_exchange(tid, 0, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+                || self.futex(FUTEX_UNLOCK_PI, None)
+        }
+
+        fn futex(&self, op: c_int, deadline: Option<&Timespec>) -> bool {
+            let result = unsafe {
+                syscall(
+                    SYS_FUTEX,
+                    self.word.as_ptr(),
+                    op | FUTEX_PRIVATE_FLAG,
+                    0 as c_int,
+                    deadline.map_or(ptr::null(), ptr::from_ref),
+                )
+            };
+            result == 0
+        }
+    }
+
+    pub(crate) const SUPPORTED: bool = true;
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+)))]
+mod futex {
+    #[derive(Default)]
+    pub(crate) struct PiLock;
+
+    impl PiLock {
+        pub(crate) fn acquire(&self) {}
+        pub(crate) fn release(&self) {}
+        pub(crate) fn wait(&self) {}
+    }
+
+    pub(crate) const SUPPORTED: bool = false;
+}
+
+pub(crate) use futex::{PiLock, SUPPORTED};
+
+impl<T> Shared<T> {
+    // Take the priority-inheriting lock after acquiring a turn, if it was configured.
+    pub(crate) fn pi_acquire(&self) {
+        if self.config.priority_inheritance {
+            self.pi.acquire();
+        }
+    }
+
+    // Release the priority-inheriting lock before giving up a turn.
+    pub(crate) fn pi_release(&self) {
+        if self.config.priority_inheritance {
+            self.pi.release();
+        }
+    }
+
+    // If priority inheritance was configured and another ticket holds the lock, block until
+    // it releases the lock, boosting its priority in the meantime. Returns false if there was
+    // nothing to wait on.
+    pub(crate) fn wait_for_holder(&self) -> bool {
+        if !self.config.priority_inheritance || self.current.load(Ordering::SeqCst) != LOCKED {
+            return false;
+        }
+        self.pi.wait();
+        true
+    }
+}
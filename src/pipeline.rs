@@ -0,0 +1,26 @@
+// Building blocks for ordered pipelines, layered on top of the ticket protocol. Compiled
+// with the `pipeline` feature, which is enabled by default.
+#[cfg(feature = "async")]
+mod arrival;
+mod collector;
+mod handoff;
+mod queue;
+mod resequence;
+mod ring;
+mod sink;
+mod staged;
+mod typed;
+mod writer;
+
+#[cfg(feature = "async")]
+pub use arrival::{Arrival, ArrivalOrder, InArrivalOrder};
+pub use collector::{Collected, OrderedCollector};
+pub use handoff::{handoff_ring, HandoffStage};
+pub use queue::{Delivery, QueuedTask, SequencedQueue};
+pub use resequence::{resequence, Resequence};
+pub(crate) use ring::{ring, Slots};
+pub use ring::{ring_buffer, RingConsumer, RingProducer, RingSlot};
+pub use sink::OrderedSink;
+pub use staged::{Stage, StageGuard};
+pub use typed::{typed_pipeline, Inlet, Outlet, TypedPipeline, TypedStage};
+pub use writer::OrderedWriter;
@@ -0,0 +1,124 @@
+use crate::{waiters::Waiters, SequexPoisoned, Timeline, Timestamp};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+/// Orders the responses of a service by the arrival of its requests, while the requests
+/// themselves are handled concurrently. Each request takes an [Arrival] when it comes in,
+/// and the future that handles it is wrapped with [Arrival::deliver], which doesn't resolve
+/// until every request that arrived before it has resolved or was dropped.
+///
+/// A middleware takes an arrival before calling the inner service, and returns the inner
+/// future wrapped in the arrival. Clones share the same order.
+#[derive(Clone)]
+pub struct ArrivalOrder {
+    inner: Arc<Inner>,
+}
+
+/// The place of a request in an [ArrivalOrder]. Dropping it without delivering a response
+/// lets the requests that arrived after it through.
+pub struct Arrival {
+    timestamp: Option<Timestamp<()>>,
+    inner: Arc<Inner>,
+}
+
+/// A future that handles a request and resolves to its response in arrival order. Created
+/// with [Arrival::deliver].
+pub struct InArrivalOrder<F: Future> {
+    future: Pin<Box<F>>,
+    output: Option<F::Output>,
+    arrival: Arrival,
+}
+
+struct Inner {
+    timeline: Timeline<()>,
+    waiters: Waiters,
+}
+
+impl ArrivalOrder {
+    /// Create an order with no requests.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                timeline: Timeline::new(()),
+                waiters: Waiters::default(),
+            }),
+        }
+    }
+
+    /// Take the next place in the order, after every request that arrived so far.
+    pub fn arrive(&self) -> Arrival {
+        Arrival {
+            timestamp: Some(self.inner.timeline.schedule_next()),
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Default for ArrivalOrder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Arrival {
+    /// Wrap the future that handles this request, so that it resolves in arrival order.
+    pub fn deliver<F: Future>(self, future: F) -> InArrivalOrder<F> {
+        InArrivalOrder {
+            future: Box::pin(future),
+            output: None,
+            arrival: self,
+        }
+    }
+
+    // Attempt to take this request's turn, registering `cx` to be woken when an earlier
+    // request is done if it is not ready.
+    fn poll_turn(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), SequexPoisoned>> {
+        let Some(timestamp) = &self.timestamp else {
+            return Poll::Ready(Err(SequexPoisoned));
+        };
+        if timestamp.try_lock()?.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        self.inner.waiters.register(cx.waker());
+        match timestamp.try_lock()? {
+            Some(_) => Poll::Ready(Ok(())),
+            None => Poll::Pending,
+        }
+    }
+}
+
+// Every request after this one is woken to check whether it's their turn.
+impl Drop for Arrival {
+    fn drop(&mut self) {
+        self.timestamp.take();
+        self.inner.waiters.wake_all();
+    }
+}
+
+// The future is boxed and the response is never pinned.
+impl<F: Future> Unpin for InArrivalOrder<F> {}
+
+impl<F: Future> Future for InArrivalOrder<F> {
+    type Output = Result<F::Output, SequexPoisoned>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if this.output.is_none() {
+            match this.future.as_mut().poll(cx) {
+                Poll::Ready(output) => this.output = Some(output),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        match this.arrival.poll_turn(cx) {
+            Poll::Ready(Ok(())) => {
+                this.arrival.inner.waiters.wake_all();
+                Poll::Ready(this.output.take().ok_or(SequexPoisoned))
+            }
+            Poll::Ready(Err(error)) => Poll::Ready(Err(error)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{Sequex, SequexPoisoned};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+
+/// A ticket that contributes one result per cycle to an ordered collection. Results can be
+/// computed in parallel, but are gathered in ticket order, and each completed cycle's results
+/// are delivered to the [Collected] handle.
+pub struct OrderedCollector<R> {
+    sequex: Sequex<Vec<R>>,
+    sender: Sender<Vec<R>>,
+}
+
+/// Receives the results gathered by a set of [OrderedCollector]s, one `Vec` per completed
+/// cycle, in cycle order.
+pub struct Collected<R> {
+    receiver: Receiver<Vec<R>>,
+}
+
+impl<R> OrderedCollector<R> {
+    /// Create the tickets of a new collection, and the handle its results are delivered to.
+    pub fn new(num_tickets: u64) -> (Vec<Self>, Collected<R>) {
+        let (sender, receiver) = mpsc::channel();
+        let collectors = Sequex::new(Vec::new(), num_tickets)
+            .into_iter()
+            .map(|sequex| Self {
+                sequex,
+                sender: sender.clone(),
+            })
+            .collect();
+        (collectors, Collected { receiver })
+    }
+
+    /// Push this ticket's result for the current cycle, blocking the current thread until it
+    /// is this ticket's turn. The last ticket completes the cycle and delivers the results.
+    /// Returns [SequexPoisoned] if the lock was poisoned.
+    pub fn push(&self, result: R) -> Result<(), SequexPoisoned> {
+        let mut results = self.sequex.lock()?;
+        results.push(result);
+        if self.sequex.ticket + 1 == self.sequex.num_tickets {
+            self.sender.send(std::mem::take(&mut *results)).ok();
+        }
+        Ok(())
+    }
+}
+
+impl<R> Collected<R> {
+    /// Block the current thread until the next cycle completes, and return its results in
+    /// ticket order. Returns [SequexPoisoned] if the collectors were dropped before another
+    /// cycle could complete.
+    pub fn wait(&self) -> Result<Vec<R>, SequexPoisoned> {
+        self.receiver.recv().map_err(|_| SequexPoisoned)
+    }
+
+    /// Return the results of the next completed cycle, if there is one, without blocking.
+    /// Returns [SequexPoisoned] if the collectors were dropped before another cycle could
+    /// complete.
+    pub fn try_wait(&self) -> Result<Option<Vec<R>>, SequexPoisoned> {
+        match self.receiver.try_recv() {
+            Ok(results) => Ok(Some(results)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(SequexPoisoned),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{pipeline::Slots, RingSlot, Sequex, SequexPoisoned};
+
+/// One stage of a ring of buffers created with [handoff_ring], which processes each buffer
+/// in place after the stage before it and hands it on to the stage after it.
+pub struct HandoffStage<T> {
+    stage: usize,
+    slots: Slots<T>,
+}
+
+/// Create a ring of `capacity` buffers, each constructed by `init`, that pass through
+/// `stages` stages in order, with a capacity of at least one. Every buffer is a sequence
+/// with a ticket for each stage, so ownership of a buffer moves from one stage to the next at
+/// the end of its turn without copying it, and the last stage hands it back to the first to
+/// be refilled. Stages run concurrently on different buffers. Returns no stages if `stages`
+/// is zero.
+///
+/// Dropping a stage poisons the ring, so the other stages observe [SequexPoisoned] from
+/// then on.
+pub fn handoff_ring<T>(
+    stages: usize,
+    capacity: usize,
+    mut init: impl FnMut() -> T,
+) -> Vec<HandoffStage<T>> {
+    let mut tickets: Vec<_> = (0..stages)
+        .map(|_| Vec::with_capacity(capacity.max(1)))
+        .collect();
+    for _ in 0..capacity.max(1) {
+        let slot = Sequex::new(init(), stages as u64);
+        for (stage, ticket) in tickets.iter_mut().zip(slot) {
+            stage.push(ticket);
+        }
+    }
+    tickets
+        .into_iter()
+        .enumerate()
+        .map(|(stage, slots)| HandoffStage {
+            stage,
+            slots: Slots::new(slots),
+        })
+        .collect()
+}
+
+impl<T> HandoffStage<T> {
+    /// Returns the position of this stage in the ring.
+    pub fn stage(&self) -> usize {
+        self.stage
+    }
+
+    /// Claim the next buffer, if the stage before this one has handed it on. Returns
+    /// [SequexPoisoned] if another stage was dropped.
+    pub fn try_claim(&mut self) -> Result<Option<RingSlot<'_, T>>, SequexPoisoned> {
+        self.slots.try_claim()
+    }
+
+    /// Claim the next buffer, blocking the current thread until the stage before this one
+    /// has handed it on. Returns [SequexPoisoned] if another stage was dropped.
+    pub fn claim(&mut self) -> Result<RingSlot<'_, T>, SequexPoisoned> {
+        self.slots.claim()
+    }
+
+    /// Returns the number of buffers in the ring.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    waiters::{self, Waiters},
+    SequexPoisoned, Timeline, Timestamp,
+};
+use std::{
+    mem,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Poll, Waker},
+};
+
+#[cfg(feature = "async")]
+use std::{future::Future, pin::Pin, task::Context};
+
+/// A queue of tasks that may run concurrently, on whatever executor or thread the caller
+/// chooses, but whose results are delivered strictly in the order the tasks were pushed.
+pub struct SequencedQueue {
+    timeline: Timeline<()>,
+}
+
+/// A task pushed onto a [SequencedQueue], to be run with [QueuedTask::run]. Dropping it
+/// without running it abandons the task, without holding up the tasks pushed after it.
+pub struct QueuedTask<R> {
+    task: Option<Box<dyn FnOnce() -> R + Send>>,
+    timestamp: Timestamp<()>,
+    slot: Arc<Slot<R>>,
+}
+
+/// A handle to the result of a [QueuedTask], which resolves once the task and every task
+/// pushed before it have run.
+///
+/// With the `async` feature enabled, the handle can also be awaited.
+pub struct Delivery<R> {
+    slot: Arc<Slot<R>>,
+}
+
+struct Slot<R> {
+    state: Mutex<State<R>>,
+    waiters: Waiters,
+}
+
+enum State<R> {
+    Waiting,
+    Ready(R),
+    Abandoned,
+    Taken,
+}
+
+impl SequencedQueue {
+    /// Create an empty queue.
+    pub fn new() -> Self {
+        Self {
+            timeline: Timeline::new(()),
+        }
+    }
+
+    /// Push a task onto the queue. Returns the task to run and a handle to its result.
+    pub fn push<R>(
+        &self,
+        task: impl FnOnce() -> R + Send + 'static,
+    ) -> (QueuedTask<R>, Delivery<R>) {
+        let slot = Arc::new(Slot {
+            state: Mutex::new(State::Waiting),
+            waiters: Waiters::default(),
+        });
+        let task = QueuedTask {
+            task: Some(Box::new(task)),
+            timestamp: self.timeline.schedule_next(),
+            slot: slot.clone(),
+        };
+        (task, Delivery { slot })
+    }
+}
+
+impl Default for SequencedQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> QueuedTask<R> {
+    /// Run the task on the current thread, then block until every task pushed before it has
+    /// delivered its result before delivering this one.
+    pub fn run(mut self) {
+        let Some(task) = self.task.take() else {
+            return;
+        };
+        let result = task();
+        let turn = self.timestamp.lock();
+        self.slot.finish(match turn {
+            Ok(_) => State::Ready(result),
+            Err(_) => State::Abandoned,
+        });
+    }
+}
+
+// A task that was never run, or panicked, never delivers its result.
+impl<R> Drop for QueuedTask<R> {
+    fn drop(&mut self) {
+        let mut state = self.slot.state();
+        if matches!(*state, State::Waiting) {
+            *state = State::Abandoned;
+            drop(state);
+            self.slot.waiters.wake_all();
+        }
+    }
+}
+
+impl<R> Delivery<R> {
+    /// Block the current thread until the result is delivered. Returns [SequexPoisoned] if the
+    /// task was dropped without running, or panicked.
+    pub fn wait(self) -> Result<R, SequexPoisoned> {
+        waiters::block_on(|waker| self.poll_result(waker))
+    }
+
+    /// Returns true if the result has been delivered, or the task was abandoned.
+    pub fn is_ready(&self) -> bool {
+        !matches!(*self.slot.state(), State::Waiting)
+    }
+
+    fn poll_result(&self, waker: &Waker) -> Poll<Result<R, SequexPoisoned>> {
+        let mut state = self.slot.state();
+        match mem::replace(&mut *state, State::Taken) {
+            State::Ready(result) => Poll::Ready(Ok(result)),
+            State::Abandoned | State::Taken => Poll::Ready(Err(SequexPoisoned)),
+            State::Waiting => {
+                *state = State::Waiting;
+                self.slot.waiters.register(waker);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<R> Slot<R> {
+    fn state(&self) -> MutexGuard<'_, State<R>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Deliver the outcome of the task. Waiters register while holding the state, so none can
+    // miss the wake up.
+    fn finish(&self, outcome: State<R>) {
+        *self.state() = outcome;
+        self.waiters.wake_all();
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R> Future for Delivery<R> {
+    type Output = Result<R, SequexPoisoned>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_result(cx.waker())
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+#[cfg(feature = "async")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Reorders the items of a source that produces them out of order, such as the results of
+/// parallel fetches, and yields them in order of their sequence numbers, starting at zero.
+/// Items that arrive early are buffered until every item before them has been yielded.
+/// Created with [resequence].
+///
+/// An iterator source is resequenced by iterating over the adaptor. With the `async`
+/// feature enabled, a source can also be a closure polling for the next item, taking a
+/// `&mut Context<'_>` and returning `Poll<Option<I>>`, and the adaptor is polled the same way
+/// with its `poll_next` method.
+///
+/// Once the source is exhausted, the items that are still buffered are yielded in order,
+/// skipping the sequence numbers that never arrived. An item whose sequence number was
+/// already yielded or skipped is dropped.
+pub struct Resequence<S, I, F> {
+    source: S,
+    key: F,
+    next: u64,
+    pending: BTreeMap<u64, I>,
+    exhausted: bool,
+}
+
+/// Resequence the items of `source` by the sequence number `key` returns for each of them.
+pub fn resequence<S, I, F: FnMut(&I) -> u64>(source: S, key: F) -> Resequence<S, I, F> {
+    Resequence {
+        source,
+        key,
+        next: 0,
+        pending: BTreeMap::new(),
+        exhausted: false,
+    }
+}
+
+impl<S, I, F: FnMut(&I) -> u64> Resequence<S, I, F> {
+    /// Returns the sequence number of the next item to be yielded.
+    pub fn next_key(&self) -> u64 {
+        self.next
+    }
+
+    /// Returns the number of items that arrived early and are waiting for their turn.
+    pub fn buffered(&self) -> usize {
+        self.pending.len()
+    }
+
+    // Buffer an item that arrived from the source, unless its turn already passed.
+    fn insert(&mut self, item: I) {
+        let key = (self.key)(&item);
+        if key >= self.next {
+            self.pending.insert(key, item);
+        }
+    }
+
+    // Take the next item if it has arrived, or the earliest buffered one once the source is
+    // exhausted.
+    fn take_ready(&mut self) -> Option<I> {
+        let entry = self.pending.first_entry()?;
+        if *entry.key() != self.next && !self.exhausted {
+            return None;
+        }
+        self.next = entry.key().saturating_add(1);
+        Some(entry.remove())
+    }
+
+    /// Returns the underlying source, dropping any buffered items.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: Iterator<Item = I>, I, F: FnMut(&I) -> u64> Iterator for Resequence<S, I, F> {
+    type Item = I;
+    fn next(&mut self) -> Option<I> {
+        loop {
+            if let Some(item) = self.take_ready() {
+                return Some(item);
+            }
+            if self.exhausted {
+                return None;
+            }
+            match self.source.next() {
+                Some(item) => self.insert(item),
+                None => self.exhausted = true,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, I, F> Resequence<S, I, F>
+where
+    S: FnMut(&mut Context<'_>) -> Poll<Option<I>> + Unpin,
+    I: Unpin,
+    F: FnMut(&I) -> u64 + Unpin,
+{
+    /// Poll the source until the next item in sequence has arrived, and yield it. Resolves to
+    /// `None` once the source is exhausted and every buffered item has been yielded.
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<I>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(item) = this.take_ready() {
+                return Poll::Ready(Some(item));
+            }
+            if this.exhausted {
+                return Poll::Ready(None);
+            }
+            match (this.source)(cx) {
+                Poll::Ready(Some(item)) => this.insert(item),
+                Poll::Ready(None) => this.exhausted = true,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
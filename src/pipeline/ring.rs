@@ -0,0 +1,165 @@
+use crate::{Guard, Sequex, SequexPoisoned};
+use std::ops::{Deref, DerefMut};
+
+/// The producing half of a ring buffer created with [ring_buffer], which fills blocks in
+/// place and hands them to the [RingConsumer] in order.
+pub struct RingProducer<T> {
+    slots: Slots<T>,
+}
+
+/// The consuming half of a ring buffer created with [ring_buffer], which receives the blocks
+/// filled by the [RingProducer] in order.
+pub struct RingConsumer<T> {
+    slots: Slots<T>,
+}
+
+/// An RAII guard over a claimed block of a ring buffer, which hands the block to the other
+/// half, or the next stage of a [handoff_ring](crate::handoff_ring), when dropped.
+pub struct RingSlot<'a, T> {
+    guard: Guard<'a, T>,
+    position: &'a mut usize,
+    capacity: usize,
+}
+
+// One ticket of each slot of the ring, and the position of the next slot to claim.
+pub(crate) struct Slots<T> {
+    slots: Box<[Sequex<T>]>,
+    position: usize,
+}
+
+/// Create a single-producer single-consumer ring buffer of `capacity` blocks, each
+/// constructed by `init`, with a capacity of at least one. Every slot is a sequence of two
+/// tickets, held by the producer and the consumer in turn, so blocks are handed off in order
+/// without copying them. Once created, claiming and handing off blocks never allocates, and
+/// in release builds takes no locks of its own, though debug builds take one to catch misuse.
+/// [RingProducer::try_claim] and [RingConsumer::try_claim] never wait for the other half:
+/// each makes a single attempt, and returns `None` if the block isn't ready yet.
+///
+/// Dropping either half poisons the ring, so the other half observes [SequexPoisoned] from
+/// then on, including for blocks that were handed off but not yet claimed.
+pub fn ring_buffer<T>(
+    capacity: usize,
+    init: impl FnMut() -> T,
+) -> (RingProducer<T>, RingConsumer<T>) {
+    ring(capacity, init, true)
+}
+
+// Create a ring buffer, which is poisoned when either half is dropped only if `poisoning` is
+// set. Otherwise the remaining half is given every turn, so a consumer can drain the blocks
+// handed off before the producer was dropped.
+pub(crate) fn ring<T>(
+    capacity: usize,
+    mut init: impl FnMut() -> T,
+    poisoning: bool,
+) -> (RingProducer<T>, RingConsumer<T>) {
+    let mut producer = Vec::with_capacity(capacity.max(1));
+    let mut consumer = Vec::with_capacity(capacity.max(1));
+    for _ in 0..capacity.max(1) {
+        let mut tickets = Sequex::builder(init(), 2)
+            .poisoning(poisoning)
+            .build()
+            .into_iter();
+        producer.extend(tickets.next());
+        consumer.extend(tickets.next());
+    }
+    (
+        RingProducer {
+            slots: Slots::new(producer),
+        },
+        RingConsumer {
+            slots: Slots::new(consumer),
+        },
+    )
+}
+
+impl<T> RingProducer<T> {
+    /// Claim the next block to fill, if the consumer has released it. Returns
+    /// [SequexPoisoned] if the consumer was dropped.
+    pub fn try_claim(&mut self) -> Result<Option<RingSlot<'_, T>>, SequexPoisoned> {
+        self.slots.try_claim()
+    }
+
+    /// Claim the next block to fill, blocking the current thread until the consumer has
+    /// released it. Returns [SequexPoisoned] if the consumer was dropped.
+    pub fn claim(&mut self) -> Result<RingSlot<'_, T>, SequexPoisoned> {
+        self.slots.claim()
+    }
+
+    /// Returns the number of blocks in the ring.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+}
+
+impl<T> RingConsumer<T> {
+    /// Claim the next filled block, if the producer has handed it off. Returns
+    /// [SequexPoisoned] if the producer was dropped.
+    pub fn try_claim(&mut self) -> Result<Option<RingSlot<'_, T>>, SequexPoisoned> {
+        self.slots.try_claim()
+    }
+
+    /// Claim the next filled block, blocking the current thread until the producer has
+    /// handed it off. Returns [SequexPoisoned] if the producer was dropped.
+    pub fn claim(&mut self) -> Result<RingSlot<'_, T>, SequexPoisoned> {
+        self.slots.claim()
+    }
+
+    /// Returns the number of blocks in the ring.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+}
+
+impl<T> Slots<T> {
+    pub(crate) fn new(slots: Vec<Sequex<T>>) -> Self {
+        Self {
+            slots: slots.into_boxed_slice(),
+            position: 0,
+        }
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub(crate) fn try_claim(&mut self) -> Result<Option<RingSlot<'_, T>>, SequexPoisoned> {
+        let capacity = self.slots.len();
+        let Some(slot) = self.slots.get(self.position) else {
+            return Ok(None);
+        };
+        Ok(slot.try_lock()?.map(|guard| RingSlot {
+            guard,
+            position: &mut self.position,
+            capacity,
+        }))
+    }
+
+    pub(crate) fn claim(&mut self) -> Result<RingSlot<'_, T>, SequexPoisoned> {
+        let capacity = self.slots.len();
+        let slot = self.slots.get(self.position).ok_or(SequexPoisoned)?;
+        Ok(RingSlot {
+            guard: slot.lock()?,
+            position: &mut self.position,
+            capacity,
+        })
+    }
+}
+
+impl<T> Drop for RingSlot<'_, T> {
+    fn drop(&mut self) {
+        *self.position = (*self.position + 1) % self.capacity;
+    }
+}
+
+impl<T> Deref for RingSlot<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for RingSlot<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{Sequex, SequexPoisoned};
+
+#[cfg(feature = "async")]
+use crate::notified::TurnTimer;
+
+#[cfg(feature = "async")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A ticket that applies the items sent to it to the protected value on its turn, one item
+/// per turn, so producers can feed an ordered aggregation. Created with [Sequex::into_sink].
+///
+/// With the `async` feature enabled, tasks can also send through the `poll_ready`,
+/// `start_send`, `poll_flush` and `poll_close` methods. An item is buffered until the
+/// ticket's turn comes, and the sink isn't ready for another until it has been applied, which
+/// applies backpressure to producers whose turn is far away.
+pub struct OrderedSink<T, I, F> {
+    sequex: Sequex<T>,
+    apply: F,
+    pending: Option<I>,
+    #[cfg(feature = "async")]
+    timer: TurnTimer,
+}
+
+impl<T> Sequex<T> {
+    /// Convert this ticket into a sink that applies each item sent to it to the value with
+    /// `apply`, taking one turn per item.
+    pub fn into_sink<I, F: FnMut(&mut T, I)>(self, apply: F) -> OrderedSink<T, I, F> {
+        OrderedSink {
+            sequex: self,
+            apply,
+            pending: None,
+            #[cfg(feature = "async")]
+            timer: TurnTimer::default(),
+        }
+    }
+}
+
+impl<T, I, F: FnMut(&mut T, I)> OrderedSink<T, I, F> {
+    /// Block the current thread until this ticket's turn, then apply `item` to the value,
+    /// after any item that is still buffered. Returns [SequexPoisoned] if the lock was
+    /// poisoned or the sequence has finished.
+    pub fn send(&mut self, item: I) -> Result<(), SequexPoisoned> {
+        if let Some(pending) = self.pending.take() {
+            let mut guard = self.sequex.lock()?;
+            (self.apply)(&mut guard, pending);
+        }
+        let mut guard = self.sequex.lock()?;
+        (self.apply)(&mut guard, item);
+        Ok(())
+    }
+
+    /// Returns the underlying ticket, dropping any buffered item.
+    pub fn into_inner(self) -> Sequex<T> {
+        self.sequex
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T, I: Unpin, F: FnMut(&mut T, I) + Unpin> OrderedSink<T, I, F> {
+    /// Wait until the sink can accept an item, applying the buffered item if there is one.
+    pub fn poll_ready(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), SequexPoisoned>> {
+        self.poll_flush(cx)
+    }
+
+    /// Buffer an item, to be applied on this ticket's next turn. Must only be called once
+    /// [OrderedSink::poll_ready] is ready, otherwise the buffered item is replaced.
+    pub fn start_send(self: Pin<&mut Self>, item: I) -> Result<(), SequexPoisoned> {
+        self.get_mut().pending = Some(item);
+        Ok(())
+    }
+
+    /// Wait for this ticket's turn and apply the buffered item, if there is one.
+    pub fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), SequexPoisoned>> {
+        let this = self.get_mut();
+        if this.pending.is_none() {
+            return Poll::Ready(Ok(()));
+        }
+        let mut guard = match this.sequex.poll_lock(cx, &mut this.timer) {
+            Poll::Ready(guard) => guard?,
+            Poll::Pending => return Poll::Pending,
+        };
+        if let Some(item) = this.pending.take() {
+            (this.apply)(&mut guard, item);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Apply the buffered item. See [OrderedSink::poll_flush].
+    pub fn poll_close(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), SequexPoisoned>> {
+        self.poll_flush(cx)
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{Guard, Sequex, SequexPoisoned};
+use std::ops::{Deref, DerefMut};
+
+/// A ticket whose position `I` in a sequence of `N` tickets is part of its type, created
+/// with [Sequex::into_stage]. Its guards can only be handed off to the stage that follows
+/// it, so code that runs consecutive stages on one thread can't take them out of order: doing
+/// so fails to compile instead of hanging at runtime.
+pub struct Stage<T, const I: usize, const N: usize> {
+    sequex: Sequex<T>,
+}
+
+/// An RAII guard of stage `I` of `N`, releasing the lock when dropped.
+pub struct StageGuard<'a, T, const I: usize, const N: usize> {
+    guard: Guard<'a, T>,
+}
+
+impl<T> Sequex<T> {
+    /// Convert this ticket into the typed stage `I` of a sequence of `N` tickets. Returns the
+    /// ticket back if it is not at position `I` of a sequence of `N` tickets.
+    pub fn into_stage<const I: usize, const N: usize>(self) -> Result<Stage<T, I, N>, Self> {
+        if self.ticket == I as u64 && self.num_tickets == N as u64 {
+            Ok(Stage { sequex: self })
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T, const I: usize, const N: usize> Stage<T, I, N> {
+    /// Attempt to take this stage's turn without blocking. See [Sequex::try_lock].
+    pub fn try_lock(&self) -> Result<Option<StageGuard<'_, T, I, N>>, SequexPoisoned> {
+        Ok(self.sequex.try_lock()?.map(|guard| StageGuard { guard }))
+    }
+
+    /// Take this stage's turn, blocking until it comes. See [Sequex::lock].
+    pub fn lock(&self) -> Result<StageGuard<'_, T, I, N>, SequexPoisoned> {
+        Ok(StageGuard {
+            guard: self.sequex.lock()?,
+        })
+    }
+
+    /// Returns the underlying ticket.
+    pub fn into_inner(self) -> Sequex<T> {
+        self.sequex
+    }
+}
+
+impl<'a, T, const I: usize, const N: usize> StageGuard<'a, T, I, N> {
+    /// Returns the cycle this turn belongs to. See [Guard::cycle].
+    pub fn cycle(&self) -> u64 {
+        self.guard.cycle()
+    }
+
+    /// Release this stage's turn and take the turn of the stage that follows it, which must
+    /// be stage `(I + 1) % N`. Handing off to any other stage is a compile-time error.
+    /// Returns [SequexPoisoned] if the lock was poisoned or the sequence has finished.
+    pub fn hand_off<'b, const J: usize>(
+        self,
+        next: &'b Stage<T, J, N>,
+    ) -> Result<StageGuard<'b, T, J, N>, SequexPoisoned> {
+        const { assert!(J == (I + 1) % N, "stages must be handed off in order") };
+        drop(self);
+        next.lock()
+    }
+}
+
+impl<T, const I: usize, const N: usize> Deref for StageGuard<'_, T, I, N> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T, const I: usize, const N: usize> DerefMut for StageGuard<'_, T, I, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{
+    pipeline::ring, wait::block, RingConsumer, RingProducer, SequexPoisoned, WaitStrategy,
+};
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Weak,
+    },
+};
+
+/// A pipeline whose stages each consume the output type of the stage before them, created
+/// with [typed_pipeline]. Stages are added with [TypedPipeline::stage] and the ends taken with
+/// [TypedPipeline::build]. Every pair of consecutive stages is linked by a ring buffer, so the
+/// stages run concurrently while items pass through them in the order they were sent.
+pub struct TypedPipeline<T, U> {
+    inlet: Inlet<T>,
+    outlet: Outlet<U>,
+    capacity: usize,
+    max_in_flight: Option<usize>,
+}
+
+/// A stage of a [TypedPipeline], which turns the items of type `I` received from the stage
+/// before it into items of type `O` for the stage after it.
+pub struct TypedStage<I, O> {
+    input: Outlet<I>,
+    output: Inlet<O>,
+}
+
+/// The sending end of a link of a [TypedPipeline]. Dropping it closes the link after the
+/// items sent so far, which may block until there is room to mark the end.
+pub struct Inlet<T> {
+    ring: RingProducer<Packet<T>>,
+    receiver: Weak<()>,
+    window: Option<Arc<Window>>,
+    closed: bool,
+}
+
+/// The receiving end of a link of a [TypedPipeline], which receives items in the order they
+/// were sent.
+pub struct Outlet<T> {
+    ring: RingConsumer<Packet<T>>,
+    _receiver: Arc<()>,
+    window: Option<Arc<Window>>,
+    closed: bool,
+}
+
+// The number of items between the two ends of a pipeline, and how many there may be.
+struct Window {
+    in_flight: AtomicUsize,
+    depth: usize,
+}
+
+// A block of the ring buffer of a link.
+enum Packet<T> {
+    Empty,
+    Item(T),
+    Closed,
+}
+
+/// Start a typed pipeline that receives items of type `T`, linking each stage to the next
+/// with room for `capacity` items, at least one.
+pub fn typed_pipeline<T>(capacity: usize) -> TypedPipeline<T, T> {
+    let (inlet, outlet) = link(capacity);
+    TypedPipeline {
+        inlet,
+        outlet,
+        capacity,
+        max_in_flight: None,
+    }
+}
+
+fn link<T>(capacity: usize) -> (Inlet<T>, Outlet<T>) {
+    // The link outlives either end, so that items sent before the inlet is dropped can still
+    // be received. A dropped outlet is detected through `receiver` instead.
+    let (producer, consumer) = ring(capacity, || Packet::Empty, false);
+    let receiver = Arc::new(());
+    (
+        Inlet {
+            ring: producer,
+            receiver: Arc::downgrade(&receiver),
+            window: None,
+            closed: false,
+        },
+        Outlet {
+            ring: consumer,
+            _receiver: receiver,
+            window: None,
+            closed: false,
+        },
+    )
+}
+
+impl<T, U> TypedPipeline<T, U> {
+    /// Add a stage that turns the items produced so far into items of type `V`, returning
+    /// the extended pipeline and the new stage, to be run on a thread of its own.
+    pub fn stage<V>(self) -> (TypedPipeline<T, V>, TypedStage<U, V>) {
+        let (output, outlet) = link(self.capacity);
+        (
+            TypedPipeline {
+                inlet: self.inlet,
+                outlet,
+                capacity: self.capacity,
+                max_in_flight: self.max_in_flight,
+            },
+            TypedStage {
+                input: self.outlet,
+                output,
+            },
+        )
+    }
+
+    /// Bound the number of items that may be in flight between the two ends of the pipeline
+    /// to `depth`, at least one, so that sending blocks until the output of the last stage
+    /// has been received instead of running further ahead of slow stages. Without a bound,
+    /// sending only blocks once the first link is full.
+    pub fn max_in_flight(mut self, depth: usize) -> Self {
+        self.max_in_flight = Some(depth.max(1));
+        self
+    }
+
+    /// Returns the end that items are sent into and the end that the output of the last
+    /// stage is received from.
+    pub fn build(mut self) -> (Inlet<T>, Outlet<U>) {
+        if let Some(depth) = self.max_in_flight {
+            let window = Arc::new(Window {
+                in_flight: AtomicUsize::new(0),
+                depth,
+            });
+            self.inlet.window = Some(window.clone());
+            self.outlet.window = Some(window);
+        }
+        (self.inlet, self.outlet)
+    }
+}
+
+impl<I, O> TypedStage<I, O> {
+    /// Receive the next item, blocking until the stage before this one has produced it, and
+    /// send the result of `f` on to the next stage. Returns false without calling `f` once
+    /// the stage before has closed, closing the next stage's input in turn. Returns
+    /// [SequexPoisoned] if the stage after this one was dropped.
+    pub fn process(&mut self, f: impl FnOnce(I) -> O) -> Result<bool, SequexPoisoned> {
+        match self.input.recv()? {
+            Some(item) => {
+                self.output.send(f(item))?;
+                Ok(true)
+            }
+            None => {
+                self.output.close()?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Process items with `f` until the stage before this one closes. See
+    /// [TypedStage::process].
+    pub fn run(mut self, mut f: impl FnMut(I) -> O) -> Result<(), SequexPoisoned> {
+        while self.process(&mut f)? {}
+        Ok(())
+    }
+}
+
+impl<T> Inlet<T> {
+    /// Send an item, blocking the current thread until the link has room for it, and the
+    /// pipeline's [TypedPipeline::max_in_flight] bound allows it. Returns [SequexPoisoned] if
+    /// the receiving end was dropped, or this end was closed.
+    pub fn send(&mut self, item: T) -> Result<(), SequexPoisoned> {
+        if self.closed || self.receiver.strong_count() == 0 {
+            return Err(SequexPoisoned);
+        }
+        if let Some(window) = &self.window {
+            block(WaitStrategy::default(), None, None, || {
+                if self.receiver.strong_count() == 0 {
+                    return Err(SequexPoisoned);
+                }
+                Ok(window
+                    .in_flight
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |in_flight| {
+                        (in_flight < window.depth).then_some(in_flight + 1)
+                    })
+                    .ok())
+            })?;
+        }
+        // An item that never made it into the ring doesn't count towards the bound.
+        let mut slot = self.ring.claim().inspect_err(|_| {
+            if let Some(window) = &self.window {
+                window.in_flight.fetch_sub(1, Ordering::SeqCst);
+            }
+        })?;
+        *slot = Packet::Item(item);
+        Ok(())
+    }
+
+    /// Close the link after the items sent so far, blocking until there is room to mark the
+    /// end. Returns [SequexPoisoned] if the receiving end was dropped.
+    pub fn close(&mut self) -> Result<(), SequexPoisoned> {
+        if self.receiver.strong_count() == 0 {
+            return Err(SequexPoisoned);
+        }
+        if !mem::replace(&mut self.closed, true) {
+            *self.ring.claim()? = Packet::Closed;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Outlet<T> {
+    /// Receive the next item, blocking the current thread until it is sent. Returns `None`
+    /// once the sending end has closed or been dropped, and every item sent before has been
+    /// received.
+    pub fn recv(&mut self) -> Result<Option<T>, SequexPoisoned> {
+        if self.closed {
+            return Ok(None);
+        }
+        let mut slot = self.ring.claim()?;
+        match mem::replace(&mut *slot, Packet::Empty) {
+            Packet::Item(item) => {
+                if let Some(window) = &self.window {
+                    window.in_flight.fetch_sub(1, Ordering::SeqCst);
+                }
+                Ok(Some(item))
+            }
+            Packet::Empty | Packet::Closed => {
+                self.closed = true;
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl<T> Drop for Inlet<T> {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+impl<T> Iterator for Outlet<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.recv().ok().flatten()
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{Guard, Sequex};
+
+#[cfg(feature = "async")]
+use crate::notified::TurnTimer;
+use std::io::{self, Write};
+
+#[cfg(feature = "async")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A ticket's view of a shared writer, which buffers the bytes written to it and writes them
+/// to the underlying writer when flushed, in ticket order. Each flush takes one turn, so that
+/// tasks assembling the parts of a response in parallel stream them out in order every cycle.
+///
+/// With the `async` feature enabled, tasks can also write through the `poll_write`,
+/// `poll_flush` and `poll_close` methods, where flushing is pending until the ticket's turn
+/// comes.
+pub struct OrderedWriter<W> {
+    sequex: Sequex<W>,
+    buffer: Vec<u8>,
+    #[cfg(feature = "async")]
+    timer: TurnTimer,
+}
+
+impl<W: Write> OrderedWriter<W> {
+    /// Create the views of a writer shared by a sequence of tickets.
+    pub fn new(writer: W, num_tickets: u64) -> Vec<Self> {
+        Sequex::new(writer, num_tickets)
+            .into_iter()
+            .map(|sequex| Self {
+                sequex,
+                buffer: Vec::new(),
+                #[cfg(feature = "async")]
+                timer: TurnTimer::default(),
+            })
+            .collect()
+    }
+
+    /// Returns the position of this view in the sequence.
+    pub fn ticket(&self) -> u64 {
+        self.sequex.ticket()
+    }
+
+    /// Returns the bytes that have been buffered since the last flush.
+    pub fn buffered(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Returns the underlying ticket, discarding any buffered bytes.
+    pub fn into_inner(self) -> Sequex<W> {
+        self.sequex
+    }
+
+    /// Buffer the bytes of `buf`. Never blocks.
+    #[cfg(feature = "async")]
+    pub fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.get_mut().write(buf))
+    }
+
+    /// Wait for this ticket's turn, then write the buffered bytes to the underlying writer.
+    #[cfg(feature = "async")]
+    pub fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        match this.sequex.poll_lock(cx, &mut this.timer) {
+            Poll::Ready(Ok(guard)) => Poll::Ready(write_turn(guard, &mut this.buffer)),
+            Poll::Ready(Err(poisoned)) => Poll::Ready(Err(io::Error::other(poisoned))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Flush the buffered bytes. See [OrderedWriter::poll_flush].
+    #[cfg(feature = "async")]
+    pub fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl<W: Write> Write for OrderedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    // Wait for this ticket's turn, then write the buffered bytes to the underlying writer.
+    fn flush(&mut self) -> io::Result<()> {
+        let guard = self.sequex.lock().map_err(io::Error::other)?;
+        write_turn(guard, &mut self.buffer)
+    }
+}
+
+// Write the buffered bytes during a turn. If the underlying writer fails, the bytes it didn't
+// take stay buffered for the next turn.
+fn write_turn<W: Write>(mut guard: Guard<'_, W>, buffer: &mut Vec<u8>) -> io::Result<()> {
+    let mut written = 0;
+    let result = loop {
+        let Some(rest) = buffer.get(written..).filter(|rest| !rest.is_empty()) else {
+            break Ok(());
+        };
+        match guard.write(rest) {
+            Ok(0) => break Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => written += n.min(rest.len()),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => break Err(error),
+        }
+    };
+    buffer.drain(..written);
+    result?;
+    guard.flush()
+}
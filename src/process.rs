@@ -0,0 +1,574 @@
+use crate::{
+    is_live,
+    wait::{block, MAX_BACKOFF},
+    SequexPoisoned, WaitStrategy, FINISHED, LOCKED, MOVED, POISON,
+};
+use std::{
+    fmt,
+    marker::PhantomData,
+    mem,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+    time::Duration,
+};
+
+/// A ticket of a sequence whose state and value live in a shared memory region, so that
+/// cooperating processes can take turns on a memory-mapped resource. One process lays out
+/// the region with [ProcessSequex::create], and every process, including that one, attaches
+/// to its tickets with [ProcessSequex::attach].
+///
+/// The value is only ever accessed in place, so it must be plain data that is valid in every
+/// process, without pointers into the memory of any one of them, and it is never dropped.
+/// Waiting tickets wait with the [WaitStrategy] they were attached with. On Linux, a ticket
+/// that parks blocks on a futex over the state word in the region, which a release in any
+/// process wakes. Elsewhere parked tickets poll the region.
+///
+/// A process that dies while holding the lock would leave the others waiting forever. With
+/// [ProcessSequex::abandon_after], a waiting ticket declares the turn abandoned once its
+/// holder has shown no sign of life for a while, failing every ticket with [SequexPoisoned]
+/// until one of them takes the turn over with [ProcessSequex::recover].
+pub struct ProcessSequex<T> {
+    header: *const Header,
+    value: *mut T,
+    ticket: u64,
+    strategy: WaitStrategy,
+    abandon_after: Option<Duration>,
+}
+
+/// An RAII guard of a [ProcessSequex], which passes the turn on when dropped. Dropping it
+/// while panicking poisons the sequence in every process.
+pub struct ProcessGuard<'a, T> {
+    sequex: &'a ProcessSequex<T>,
+    // The ticket whose turn is held, which is another ticket's for a recovered turn.
+    ticket: u64,
+    _not_send: PhantomData<*mut T>,
+}
+
+/// An error returned when a shared memory region can't hold a sequence, or holds no
+/// sequence to attach to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AttachError {
+    /// The region is null, too small or misaligned for the sequence.
+    InvalidRegion,
+    /// No sequence has been created in the region.
+    Uninitialized,
+    /// The sequence has no such ticket.
+    NoSuchTicket,
+    /// The sequence was given no tickets.
+    NoTickets,
+    /// The sequence was given more tickets than can be numbered.
+    TooManyTickets,
+}
+
+// The state of the sequence at the start of the region, followed by the value. `magic` is
+// written last, so a region that has it is ready to be attached to.
+#[repr(C)]
+struct Header {
+    magic: AtomicU64,
+    num_tickets: AtomicU64,
+    current: AtomicU64,
+    cycle: AtomicU64,
+    // The ticket holding the lock, and when it last showed a sign of life, in milliseconds
+    // of the monotonic clock, which every process on the machine shares. Both are written
+    // before the lock is taken, and the beat once more as the lock is released, so a holder
+    // that dies at any point leaves a beat behind that goes stale. The beat is `ABANDONED`
+    // once a waiter gave up on the holder.
+    holder: AtomicU64,
+    beat: AtomicU64,
+}
+
+// A turn whose holder was given up on. Process sequences never move their value out, so the
+// state word uses MOVED for it.
+const ABANDONED: u64 = MOVED;
+
+const MAGIC: u64 = u64::from_le_bytes(*b"sequex\0\x01");
+
+unsafe impl<T: Send> Send for ProcessSequex<T> {}
+unsafe impl<T: Send> Sync for ProcessSequex<T> {}
+
+impl<T: Copy> ProcessSequex<T> {
+    /// Returns the number of bytes a shared memory region needs to hold a sequence over a
+    /// value of type `T`.
+    pub const fn region_len() -> usize {
+        Self::value_offset() + mem::size_of::<T>()
+    }
+
+    /// Returns the alignment a shared memory region needs to hold a sequence over a value of
+    /// type `T`. Memory mappings are aligned to pages, which is always enough.
+    pub const fn region_align() -> usize {
+        let header = mem::align_of::<Header>();
+        let value = mem::align_of::<T>();
+        if header > value {
+            header
+        } else {
+            value
+        }
+    }
+
+    const fn value_offset() -> usize {
+        mem::size_of::<Header>().next_multiple_of(mem::align_of::<T>())
+    }
+
+    /// Lay out a sequence of `num_tickets` tickets over `value` in a shared memory region of
+    /// `len` bytes, starting at the first ticket. Any sequence the region held before is
+    /// replaced.
+    ///
+    /// # Safety
+    ///
+    /// `region` must be valid for reads and writes of `len` bytes for as long as any ticket
+    /// is attached to it, in this process or the others, and no other process may attach to
+    /// it until this returns.
+    pub unsafe fn create(
+        region: *mut u8,
+        len: usize,
+        value: T,
+        num_tickets: u64,
+    ) -> Result<(), AttachError> {
+        if num_tickets == 0 {
+            return Err(AttachError::NoTickets);
+        }
+        if num_tickets >= FINISHED {
+            return Err(AttachError::TooManyTickets);
+        }
+        Self::check_region(region, len)?;
+        let header = region.cast::<Header>();
+        header.write(Header {
+            magic: AtomicU64::new(0),
+            num_tickets: AtomicU64::new(num_tickets),
+            current: AtomicU64::new(0),
+            cycle: AtomicU64::new(0),
+            holder: AtomicU64::new(0),
+            beat: AtomicU64::new(0),
+        });
+        region.add(Self::value_offset()).cast::<T>().write(value);
+        (*header).magic.store(MAGIC, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Attach to `ticket` of the sequence created in a shared memory region of `len` bytes,
+    /// which may be mapped at a different address than in the process that created it. Each
+    /// ticket must only be attached once across all processes.
+    ///
+    /// # Safety
+    ///
+    /// `region` must be valid for reads and writes of `len` bytes for as long as the ticket
+    /// is alive, and the sequence in it must have been created over a value of type `T`.
+    pub unsafe fn attach(region: *mut u8, len: usize, ticket: u64) -> Result<Self, AttachError> {
+        Self::check_region(region, len)?;
+        let header = region.cast::<Header>().cast_const();
+        if (*header).magic.load(Ordering::SeqCst) != MAGIC {
+            return Err(AttachError::Uninitialized);
+        }
+        if ticket >= (*header).num_tickets.load(Ordering::SeqCst) {
+            return Err(AttachError::NoSuchTicket);
+        }
+        Ok(Self {
+            header,
+            value: region.add(Self::value_offset()).cast(),
+            ticket,
+            strategy: WaitStrategy::default(),
+            abandon_after: None,
+        })
+    }
+
+    fn check_region(region: *mut u8, len: usize) -> Result<(), AttachError> {
+        if region.is_null()
+            || len < Self::region_len()
+            || !(region as usize).is_multiple_of(Self::region_align())
+        {
+            return Err(AttachError::InvalidRegion);
+        }
+        Ok(())
+    }
+}
+
+impl<T> ProcessSequex<T> {
+    /// Set how [ProcessSequex::lock] waits for the turn. Backoff polls the region at least
+    /// every 10ms, spinning polls it continuously. Hold times aren't measured across
+    /// processes, so the adaptive strategy waits like backoff.
+    pub fn with_wait_strategy(mut self, strategy: WaitStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Declare the turn abandoned if its holder shows no sign of life for `timeout` while
+    /// this ticket is waiting. A holder shows signs of life by acquiring the lock and with
+    /// [ProcessGuard::heartbeat], so `timeout` should be well above the longest a turn goes
+    /// without either.
+    pub fn abandon_after(mut self, timeout: Duration) -> Self {
+        self.abandon_after = Some(timeout);
+        self
+    }
+
+    /// Returns true if a waiting ticket gave up on the holder of a turn. See
+    /// [ProcessSequex::abandon_after].
+    pub fn is_abandoned(&self) -> bool {
+        self.header().current.load(Ordering::SeqCst) == ABANDONED
+    }
+
+    /// Take over an abandoned turn, returning a guard over the value the dead holder left
+    /// behind, which may have been modified partway. Once the guard is dropped, the turn
+    /// passes on from the abandoned ticket as if its holder had released it, and the
+    /// sequence carries on. A process taking the abandoned ticket's place can attach to it
+    /// again. Returns `None` if the turn isn't abandoned, or another ticket took it over.
+    pub fn recover(&self) -> Option<ProcessGuard<'_, T>> {
+        let header = self.header();
+        header
+            .current
+            .compare_exchange(ABANDONED, LOCKED, Ordering::SeqCst, Ordering::SeqCst)
+            .ok()?;
+        header.beat.store(now(), Ordering::SeqCst);
+        Some(ProcessGuard {
+            sequex: self,
+            ticket: header.holder.load(Ordering::SeqCst),
+            _not_send: PhantomData,
+        })
+    }
+
+    /// Returns the position of this ticket in the sequence.
+    pub fn ticket(&self) -> u64 {
+        self.ticket
+    }
+
+    /// Returns the number of tickets of the sequence.
+    pub fn num_tickets(&self) -> u64 {
+        self.header().num_tickets.load(Ordering::SeqCst)
+    }
+
+    /// Attempt to acquire the lock without blocking. Returns [SequexPoisoned] if a process
+    /// poisoned the sequence.
+    pub fn try_lock(&self) -> Result<Option<ProcessGuard<'_, T>>, SequexPoisoned> {
+        let header = self.header();
+        // Only this ticket takes the turn once it is its own, so the holder and beat can be
+        // published before the lock is, leaving no moment where it is held without them.
+        if header.current.load(Ordering::SeqCst) == self.ticket {
+            header.holder.store(self.ticket, Ordering::SeqCst);
+            header.beat.store(now(), Ordering::SeqCst);
+        }
+        match header.current.compare_exchange(
+            self.ticket,
+            LOCKED,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        ) {
+            Ok(_) => Ok(Some(ProcessGuard {
+                sequex: self,
+                ticket: self.ticket,
+                _not_send: PhantomData,
+            })),
+            Err(current) if !is_live(current) => Err(SequexPoisoned),
+            Err(LOCKED) if self.abandon_stale() => Err(SequexPoisoned),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Acquire the lock, blocking the current thread until this ticket's turn comes. Returns
+    /// [SequexPoisoned] if a process poisoned the sequence.
+    pub fn lock(&self) -> Result<ProcessGuard<'_, T>, SequexPoisoned> {
+        if self.strategy == WaitStrategy::Spin {
+            return block(self.strategy, None, None, || self.try_lock());
+        }
+        let current = &self.header().current;
+        // The backoff bounds how long a waiter goes without checking for an abandoned turn.
+        let mut backoff = 100;
+        loop {
+            let observed = current.load(Ordering::SeqCst);
+            if let Some(guard) = self.try_lock()? {
+                return Ok(guard);
+            }
+            futex::wait(current, observed, Duration::from_micros(backoff));
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    /// Poison the sequence, failing every ticket in every process with [SequexPoisoned]. A
+    /// lock that is held stays valid until its guard is dropped.
+    pub fn poison(&self) {
+        let current = &self.header().current;
+        let poisoned = current.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+            is_live(current).then_some(POISON)
+        });
+        if poisoned.is_ok() {
+            futex::wake(current);
+        }
+    }
+
+    // Declare the turn abandoned if its holder went quiet for too long. Returns true if the
+    // turn is abandoned.
+    fn abandon_stale(&self) -> bool {
+        let Some(timeout) = self.abandon_after else {
+            return false;
+        };
+        let header = self.header();
+        let beat = header.beat.load(Ordering::SeqCst);
+        if beat == ABANDONED {
+            return true;
+        }
+        if now().saturating_sub(beat) <= timeout.as_millis() as u64 {
+            return false;
+        }
+        // Releasing the lock and taking the next turn both stamp a newer beat, so the
+        // exchange fails unless the holder that went quiet still holds the lock.
+        if header
+            .beat
+            .compare_exchange(beat, ABANDONED, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return false;
+        }
+        // The sequence may have been poisoned while the turn was held, which must stick.
+        let _ =
+            header
+                .current
+                .compare_exchange(LOCKED, ABANDONED, Ordering::SeqCst, Ordering::SeqCst);
+        futex::wake(&header.current);
+        true
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { &*self.header }
+    }
+}
+
+impl<T> ProcessGuard<'_, T> {
+    /// Returns the cycle this turn belongs to, counting from zero.
+    pub fn cycle(&self) -> u64 {
+        self.sequex.header().cycle.load(Ordering::SeqCst)
+    }
+
+    /// Show that the holder is still alive, so that waiting tickets don't declare the turn
+    /// abandoned. Returns [SequexPoisoned] if they already did, in which case the turn is
+    /// no longer held and the value must be left alone. See [ProcessSequex::abandon_after].
+    pub fn heartbeat(&self) -> Result<(), SequexPoisoned> {
+        self.sequex
+            .header()
+            .beat
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |beat| {
+                (beat != ABANDONED).then(now)
+            })
+            .map(drop)
+            .map_err(|_| SequexPoisoned)
+    }
+}
+
+impl<T> Deref for ProcessGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.sequex.value }
+    }
+}
+
+impl<T> DerefMut for ProcessGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.sequex.value }
+    }
+}
+
+impl<T> Drop for ProcessGuard<'_, T> {
+    fn drop(&mut self) {
+        let header = self.sequex.header();
+        // A turn that was declared abandoned is left for a ticket to recover. Otherwise the
+        // beat is stamped once more, so that a waiter can't give up on the turn as it passes
+        // on, and one that goes stale if this process dies before it does.
+        if header
+            .beat
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |beat| {
+                (beat != ABANDONED).then(now)
+            })
+            .is_err()
+        {
+            return;
+        }
+        let wrapped = self.ticket + 1 == header.num_tickets.load(Ordering::SeqCst);
+        let next = if thread::panicking() {
+            POISON
+        } else if wrapped {
+            0
+        } else {
+            self.ticket + 1
+        };
+        // The cycle is counted before the turn passes on, so that the next holder sees it,
+        // and the count is taken back if the turn doesn't pass on after all.
+        let counted = wrapped && next != POISON;
+        if counted {
+            header.cycle.fetch_add(1, Ordering::SeqCst);
+        }
+        let passed =
+            header
+                .current
+                .compare_exchange(LOCKED, next, Ordering::SeqCst, Ordering::SeqCst);
+        if passed.is_err() && counted {
+            header.cycle.fetch_sub(1, Ordering::SeqCst);
+        }
+        match passed {
+            Ok(_) => futex::wake(&header.current),
+            // Poisoned by another process while the turn was held, which must stick.
+            Err(POISON) => {}
+            // Anything else means the state word was written by someone not holding the
+            // turn, so the sequence can't be trusted any more.
+            Err(_) => self.sequex.poison(),
+        }
+    }
+}
+
+impl fmt::Display for AttachError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRegion => write!(f, "shared memory region is too small or misaligned"),
+            Self::Uninitialized => write!(f, "no sequence was created in the region"),
+            Self::NoSuchTicket => write!(f, "the sequence has no such ticket"),
+            Self::NoTickets => write!(f, "sequence has no tickets"),
+            Self::TooManyTickets => write!(f, "sequence has too many tickets"),
+        }
+    }
+}
+
+impl std::error::Error for AttachError {}
+
+// The current time in milliseconds of the monotonic clock, never `ABANDONED`.
+fn now() -> u64 {
+    clock::monotonic_millis().min(ABANDONED - 1)
+}
+
+// A clock that every process on the machine reads alike, and that wall-clock changes don't
+// step.
+#[cfg(target_os = "linux")]
+mod clock {
+    use std::ffi::{c_int, c_long};
+
+    extern "C" {
+        fn clock_gettime(clock: c_int, time: *mut Timespec) -> c_int;
+    }
+
+    const CLOCK_MONOTONIC: c_int = 1;
+
+    #[repr(C)]
+    struct Timespec {
+        tv_sec: c_long,
+        tv_nsec: c_long,
+    }
+
+    pub(super) fn monotonic_millis() -> u64 {
+        let mut time = Timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        if unsafe { clock_gettime(CLOCK_MONOTONIC, &mut time) } != 0 {
+            return 0;
+        }
+        (time.tv_sec as u64) * 1000 + (time.tv_nsec as u64) / 1_000_000
+    }
+}
+
+// Elsewhere the wall clock is the only one shared between processes.
+#[cfg(not(target_os = "linux"))]
+mod clock {
+    use std::time::SystemTime;
+
+    pub(super) fn monotonic_millis() -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |now| now.as_millis() as u64)
+    }
+}
+
+// Waiting on the state word in the region, across processes.
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+))]
+mod futex {
+    use std::{
+        ffi::{c_int, c_long},
+        ptr,
+        sync::atomic::AtomicU64,
+        time::Duration,
+    };
+
+    extern "C" {
+        fn syscall(num: c_long, ...) -> c_long;
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_FUTEX: c_long = 202;
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+    const SYS_FUTEX: c_long = 98;
+
+    // Without FUTEX_PRIVATE_FLAG, so that processes mapping the region share the futex.
+    const FUTEX_WAIT: c_int = 0;
+    const FUTEX_WAKE: c_int = 1;
+
+    #[repr(C)]
+    struct Timespec {
+        tv_sec: c_long,
+        tv_nsec: c_long,
+    }
+
+    // Futexes are 32 bits wide, so they wait on the low half of the state word. A change that
+    // leaves it as it was goes unnoticed until the timeout, which bounds every wait.
+    fn low_half(word: &AtomicU64) -> *const u32 {
+        let word = word.as_ptr().cast::<u32>().cast_const();
+        if cfg!(target_endian = "big") {
+            word.wrapping_add(1)
+        } else {
+            word
+        }
+    }
+
+    // Block until the state word is woken or the timeout elapses, unless it no longer holds
+    // `observed`.
+    pub(super) fn wait(word: &AtomicU64, observed: u64, timeout: Duration) {
+        let timeout = Timespec {
+            tv_sec: timeout.as_secs() as c_long,
+            tv_nsec: timeout.subsec_nanos() as c_long,
+        };
+        unsafe {
+            syscall(
+                SYS_FUTEX,
+                low_half(word),
+                FUTEX_WAIT,
+                observed as u32,
+                &timeout as *const Timespec,
+            );
+        }
+    }
+
+    // Wake every ticket waiting on the state word, in any process.
+    pub(super) fn wake(word: &AtomicU64) {
+        unsafe {
+            syscall(
+                SYS_FUTEX,
+                low_half(word),
+                FUTEX_WAKE,
+                c_int::MAX,
+                ptr::null::<Timespec>(),
+            );
+        }
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+)))]
+mod futex {
+    use std::{sync::atomic::AtomicU64, time::Duration};
+
+    pub(super) fn wait(_word: &AtomicU64, _observed: u64, timeout: Duration) {
+        std::thread::park_timeout(timeout);
+    }
+
+    pub(super) fn wake(word: &AtomicU64) {
+        crate::wait::notify(word);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{Guard, Sequex};
+use std::mem;
+
+impl<T> Sequex<T> {
+    /// Convert the ticket into a raw pointer, eg. to store it in the context of a C callback.
+    /// The ticket stays in the sequence, and must be converted back with [Sequex::from_raw]
+    /// to be dropped, otherwise its turns are never taken or passed on.
+    pub fn into_raw(self) -> *mut Sequex<T> {
+        Box::into_raw(Box::new(self))
+    }
+
+    /// Reconstitute a ticket from a pointer returned by [Sequex::into_raw].
+    ///
+    /// # Safety
+    ///
+    /// `raw` must have been returned by [Sequex::into_raw] for a ticket of the same type, and
+    /// must only be converted back once. No guard reconstituted with [Guard::from_raw] from
+    /// it may still be alive.
+    pub unsafe fn from_raw(raw: *mut Sequex<T>) -> Sequex<T> {
+        *Box::from_raw(raw)
+    }
+}
+
+impl<'a, T> Guard<'a, T> {
+    /// Keep the turn held without a guard, returning a pointer to the ticket holding it, so
+    /// that a turn can be parked across a C library's asynchronous callback. The turn is only
+    /// passed on once the guard is reconstituted with [Guard::from_raw] and dropped.
+    pub fn into_raw(self) -> *const Sequex<T> {
+        let sequex = self.sequex as *const Sequex<T>;
+        // The parked turn isn't held by this thread anymore, which can take other turns.
+        self.sequex.shared.set_locked_by(None);
+        mem::forget(self);
+        sequex
+    }
+
+    /// Reconstitute a guard from a pointer returned by [Guard::into_raw]. The value is
+    /// assumed to have been modified, bumping its version when the guard is dropped.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must have been returned by [Guard::into_raw] for a guard of the same type, and
+    /// must only be converted back once. The ticket it points to must stay alive, in place,
+    /// for `'a`, and the turn must not be resumed on a thread other than the one it was
+    /// acquired on if the value isn't [Send].
+    pub unsafe fn from_raw(raw: *const Sequex<T>) -> Guard<'a, T> {
+        let sequex = &*raw;
+        sequex.shared.set_locked_by(Some(sequex.ticket));
+        Guard {
+            sequex,
+            mutated: true,
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    waiters::{self, Waiters},
+    Sequex, SequexPoisoned, Shared,
+};
+use std::{
+    ops::Deref,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, PoisonError, RwLock,
+    },
+    task::{Poll, Waker},
+};
+
+#[cfg(feature = "async")]
+use std::{future::Future, pin::Pin, task::Context};
+
+/// A read handle that loads the value as it was published after the latest turn, without
+/// joining the rotation or waiting for the lock. Created with [Sequex::rcu_reader]. Readers
+/// don't keep the sequence alive.
+///
+/// A reader can also wait for the next snapshot to be published, with
+/// [RcuReader::wait_for_change], or with `RcuReader::changed` when the `async` feature is
+/// enabled.
+pub struct RcuReader<T> {
+    rcu: Arc<Rcu<T>>,
+    seen: u64,
+}
+
+/// A future that resolves with the next snapshot published to an [RcuReader]. Created with
+/// [RcuReader::changed].
+#[cfg(feature = "async")]
+pub struct Changed<'a, T> {
+    reader: &'a mut RcuReader<T>,
+}
+
+/// An immutable snapshot of the value loaded by an [RcuReader], tagged with the epoch it was
+/// published at. A snapshot is freed once every reader that loaded it has dropped it.
+pub struct RcuSnapshot<T> {
+    value: Arc<T>,
+    epoch: u64,
+}
+
+// The value as published after the latest turn, how to clone the next one, and the readers
+// waiting for it.
+pub(crate) struct Rcu<T> {
+    latest: RwLock<RcuSnapshot<T>>,
+    clone: fn(&T) -> T,
+    waiters: Waiters,
+    ended: AtomicBool,
+}
+
+impl<T: Clone + Send + Sync> Sequex<T> {
+    /// Returns a read handle that loads immutable snapshots of the value without taking a
+    /// turn. Once a sequence has a reader, the value is cloned and published at the end of
+    /// every turn that accessed it mutably, so the writers pay for the reads, which suits
+    /// values that are read much more often than they are written. Blocks while another
+    /// ticket holds the lock the first time a reader is created. Returns [SequexPoisoned] if
+    /// the lock was poisoned.
+    pub fn rcu_reader(&self) -> Result<RcuReader<T>, SequexPoisoned> {
+        let rcu = self.shared.peek(|value, version| {
+            self.shared
+                .rcu
+                .get_or_init(|| {
+                    Arc::new(Rcu {
+                        latest: RwLock::new(RcuSnapshot {
+                            value: Arc::new(value.clone()),
+                            epoch: version,
+                        }),
+                        clone: T::clone,
+                        waiters: Waiters::default(),
+                        ended: AtomicBool::new(false),
+                    })
+                })
+                .clone()
+        })?;
+        let seen = rcu.load().epoch;
+        Ok(RcuReader { rcu, seen })
+    }
+}
+
+impl<T> RcuReader<T> {
+    /// Load the latest published snapshot of the value. Keeps returning the last snapshot
+    /// once the sequence has ended.
+    pub fn load(&self) -> RcuSnapshot<T> {
+        self.rcu.load()
+    }
+
+    /// Block the current thread until a snapshot newer than the last one this reader waited
+    /// for is published, and return it. Returns [SequexPoisoned] once the sequence has ended
+    /// and every snapshot has been seen.
+    pub fn wait_for_change(&mut self) -> Result<RcuSnapshot<T>, SequexPoisoned> {
+        waiters::block_on(|waker| self.poll_change(waker))
+    }
+
+    /// Wait for a snapshot newer than the last one this reader waited for. See
+    /// [RcuReader::wait_for_change]. Only available with the `async` feature.
+    #[cfg(feature = "async")]
+    pub fn changed(&mut self) -> Changed<'_, T> {
+        Changed { reader: self }
+    }
+
+    fn poll_change(&mut self, waker: &Waker) -> Poll<Result<RcuSnapshot<T>, SequexPoisoned>> {
+        if let Poll::Ready(result) = self.check_change() {
+            return Poll::Ready(result);
+        }
+        self.rcu.waiters.register(waker);
+        self.check_change()
+    }
+
+    fn check_change(&mut self) -> Poll<Result<RcuSnapshot<T>, SequexPoisoned>> {
+        let snapshot = self.rcu.load();
+        if snapshot.epoch > self.seen {
+            self.seen = snapshot.epoch;
+            Poll::Ready(Ok(snapshot))
+        } else if self.rcu.ended.load(Ordering::SeqCst) {
+            Poll::Ready(Err(SequexPoisoned))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Clone for RcuReader<T> {
+    fn clone(&self) -> Self {
+        Self {
+            rcu: self.rcu.clone(),
+            seen: self.seen,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Future for Changed<'_, T> {
+    type Output = Result<RcuSnapshot<T>, SequexPoisoned>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().reader.poll_change(cx.waker())
+    }
+}
+
+impl<T> Rcu<T> {
+    fn load(&self) -> RcuSnapshot<T> {
+        self.latest
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    // Wake every waiting reader once the sequence has ended.
+    pub(crate) fn end(&self) {
+        self.ended.store(true, Ordering::SeqCst);
+        self.waiters.wake_all();
+    }
+}
+
+impl<T> RcuSnapshot<T> {
+    /// Returns the epoch the snapshot was published at, which is the
+    /// [version](crate::Guard::version) of the value.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+}
+
+impl<T> Clone for RcuSnapshot<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            epoch: self.epoch,
+        }
+    }
+}
+
+impl<T> Deref for RcuSnapshot<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> Shared<T> {
+    // Publish the value to readers, if there are any, at the end of a turn that bumped its
+    // version to `version`. Must only be called while holding the lock.
+    pub(crate) fn publish(&self, version: u64) {
+        let Some(rcu) = self.rcu.get() else {
+            return;
+        };
+        if !self.initialized.load(Ordering::SeqCst) {
+            return;
+        }
+        let value = (rcu.clone)(unsafe { (*self.value.get()).assume_init_ref() });
+        *rcu.latest.write().unwrap_or_else(PoisonError::into_inner) = RcuSnapshot {
+            value: Arc::new(value),
+            epoch: version,
+        };
+        rcu.waiters.wake_all();
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{Guard, Sequex, SequexPoisoned};
+
+#[cfg(feature = "sync")]
+use crate::Observer;
+use std::ops::Deref;
+
+/// A ticket that can only read the value when it takes its turn, created with
+/// [Sequex::into_read_only]. Stages that merely observe the value can't mutate it by accident,
+/// since their guards only deref to `&T`.
+pub struct ReadOnly<T> {
+    sequex: Sequex<T>,
+}
+
+/// An RAII guard of a [ReadOnly] ticket that releases the lock when dropped.
+pub struct ReadGuard<'a, T> {
+    guard: Guard<'a, T>,
+}
+
+impl<T> Sequex<T> {
+    /// Convert this ticket into one that can only read the value. Its position in the
+    /// sequence is unchanged.
+    pub fn into_read_only(mut self) -> ReadOnly<T> {
+        self.read_only = true;
+        ReadOnly { sequex: self }
+    }
+}
+
+impl<T> ReadOnly<T> {
+    /// Returns the position of this ticket in the sequence.
+    pub fn ticket(&self) -> u64 {
+        self.sequex.ticket()
+    }
+
+    /// Attempt to acquire the lock, without blocking the current thread. See
+    /// [Sequex::try_lock].
+    pub fn try_lock(&self) -> Result<Option<ReadGuard<'_, T>>, SequexPoisoned> {
+        Ok(self.sequex.try_lock()?.map(|guard| ReadGuard { guard }))
+    }
+
+    /// Acquire the lock, blocking the current thread until it is this ticket's turn. See
+    /// [Sequex::lock].
+    pub fn lock(&self) -> Result<ReadGuard<'_, T>, SequexPoisoned> {
+        Ok(ReadGuard {
+            guard: self.sequex.lock()?,
+        })
+    }
+
+    /// Acquire the lock and run `f` on the value, releasing the lock once it returns. See
+    /// [Sequex::lock_with].
+    pub fn lock_with<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, SequexPoisoned> {
+        self.sequex
+            .run_with(self.sequex.lock()?, |guard| f(&**guard))
+    }
+
+    /// Wait for this ticket's turn and pass it to the next ticket. See [Sequex::pass].
+    pub fn pass(&self) -> Result<(), SequexPoisoned> {
+        self.sequex.pass()
+    }
+
+    /// Create an observer of the sequence. See [Sequex::observer]. Only available with the
+    /// `sync` feature.
+    #[cfg(feature = "sync")]
+    pub fn observer(&self) -> Observer<T> {
+        self.sequex.observer()
+    }
+
+    /// Returns the underlying ticket, which can mutate the value again.
+    pub fn into_inner(mut self) -> Sequex<T> {
+        self.sequex.read_only = false;
+        self.sequex
+    }
+}
+
+impl<T> ReadGuard<'_, T> {
+    /// Returns the cycle this turn belongs to. See [Guard::cycle].
+    pub fn cycle(&self) -> u64 {
+        self.guard.cycle()
+    }
+}
+
+impl<T> Deref for ReadGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
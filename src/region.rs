@@ -0,0 +1,18 @@
+use crate::Sequex;
+use std::slice;
+
+impl<T> Sequex<&mut [T]> {
+    /// Create a new sequence over a region of memory the crate doesn't own, such as a memory
+    /// map or a mapped device buffer, so the turn order protects data that other code manages.
+    /// Guards dereference to the region, which can be indexed and passed on as `&mut [T]`
+    /// directly. A region already borrowed as a slice, or owned by a handle that dereferences
+    /// to it, can be guarded with [Sequex::new] instead.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for reads and writes of `len` elements of `T`, and must not be
+    /// accessed other than through the sequence, for as long as any of its tickets is alive.
+    pub unsafe fn from_raw_region(ptr: *mut T, len: usize, num_tickets: u64) -> Vec<Self> {
+        Sequex::new(slice::from_raw_parts_mut(ptr, len), num_tickets)
+    }
+}
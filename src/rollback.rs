@@ -0,0 +1,68 @@
+use crate::{Builder, Shared};
+use std::cell::UnsafeCell;
+
+// A copy of the value taken at the start of each turn, to restore if the holder panics. Only
+// accessed by the holder of the lock.
+pub(crate) struct Rollback<T> {
+    clone: fn(&T) -> T,
+    saved: UnsafeCell<Option<T>>,
+}
+
+impl<T: Clone> Builder<T> {
+    /// Keep a copy of the value from the start of every turn, and restore it if the holder
+    /// panics, passing the turn on instead of poisoning the sequence. A panicking turn is then
+    /// skipped, as if it never happened, at the cost of cloning the value on every turn. A
+    /// ticket dropped by a panicking thread is retired and skipped from then on, instead of
+    /// poisoning the sequence. [Sequex::lock_with](crate::Sequex::lock_with) resumes the
+    /// panic once the turn has been rolled back.
+    pub fn rollback_on_panic(mut self) -> Self {
+        self.rollback = Some(Rollback {
+            clone: T::clone,
+            saved: UnsafeCell::new(None),
+        });
+        self
+    }
+}
+
+impl<T> Rollback<T> {
+    // The same rollback, for a successor of the sequence.
+    pub(crate) fn renewed(&self) -> Self {
+        Self {
+            clone: self.clone,
+            saved: UnsafeCell::new(None),
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    // Save a copy of the value as a turn starts. Must only be called while holding the lock,
+    // once the value has been initialized.
+    pub(crate) unsafe fn save_rollback(&self) {
+        if let Some(rollback) = &self.rollback {
+            let value = (*self.value.get()).assume_init_ref();
+            *rollback.saved.get() = Some((rollback.clone)(value));
+        }
+    }
+
+    // Restore the copy saved at the start of the turn, if there is one, returning true if the
+    // value was restored. Must only be called while holding the lock.
+    pub(crate) unsafe fn roll_back(&self) -> bool {
+        let Some(saved) = self.take_rollback() else {
+            return false;
+        };
+        *(*self.value.get()).assume_init_mut() = saved;
+        true
+    }
+
+    // Take the copy saved at the start of the turn, to be dropped as the turn ends. Must only
+    // be called while holding the lock.
+    pub(crate) unsafe fn take_rollback(&self) -> Option<T> {
+        self.rollback
+            .as_ref()
+            .and_then(|rollback| (*rollback.saved.get()).take())
+    }
+
+    pub(crate) fn rolls_back(&self) -> bool {
+        self.rollback.is_some()
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{Guard, Sequex, SequexPoisoned};
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+/// The rule that decides which of the rotations sharing a value gets it next, when the
+/// current tickets of several of them are waiting for it at once. Only tickets whose turn it
+/// is in their own rotation ever wait for the value, so there is at most one per rotation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Arbitration {
+    /// The rotations are served in the order their tickets started waiting for the value.
+    #[default]
+    FirstCome,
+    /// The rotations take turns, starting after the rotation that last held the value, so
+    /// none of them can be starved by the others.
+    Alternate,
+    /// The rotation that was listed first wins, so a rotation only gets the value while those
+    /// before it have no ticket waiting.
+    Priority,
+}
+
+/// A ticket of one of several independent rotations over a single value, such as an ingest
+/// order and a flush order over the same buffer. Each rotation has its own tickets and turn
+/// order, and a ticket first waits for its turn in its own rotation, then for the value,
+/// which is handed between the rotations by their [Arbitration] rule. Created with
+/// [Rotation::new].
+///
+/// Each rotation poisons on its own when one of its tickets is dropped, and the others carry
+/// on. A ticket that panics while holding the value poisons it for every rotation.
+pub struct Rotation<T> {
+    sequex: Sequex<()>,
+    rotation: usize,
+    shared: Arc<Rotations<T>>,
+}
+
+/// An RAII guard of a [Rotation] ticket, which holds both its turn and the value, and hands
+/// the value on to the next rotation before passing on the turn when dropped.
+pub struct RotationGuard<'a, T> {
+    value: MutexGuard<'a, T>,
+    _grant: Grant<'a>,
+    turn: Guard<'a, ()>,
+}
+
+// The value shared by the rotations, and who may take it next.
+struct Rotations<T> {
+    arbiter: Arbiter,
+    value: Mutex<T>,
+}
+
+struct Arbiter {
+    arbitration: Arbitration,
+    state: Mutex<ArbiterState>,
+    released: Condvar,
+}
+
+struct ArbiterState {
+    held: bool,
+    // When the ticket of each rotation that is waiting started to.
+    waiting: Vec<Option<u64>>,
+    arrivals: u64,
+    last: usize,
+}
+
+// Hands the value on when dropped.
+struct Grant<'a>(&'a Arbiter);
+
+impl<T> Rotation<T> {
+    /// Create rotations over `value`, with the number of tickets of each given by
+    /// `num_tickets`, returning the tickets of each rotation in the order they were listed.
+    pub fn new(value: T, num_tickets: &[u64], arbitration: Arbitration) -> Vec<Vec<Self>> {
+        let shared = Arc::new(Rotations {
+            arbiter: Arbiter {
+                arbitration,
+                state: Mutex::new(ArbiterState {
+                    held: false,
+                    waiting: vec![None; num_tickets.len()],
+                    arrivals: 0,
+                    last: num_tickets.len().saturating_sub(1),
+                }),
+                released: Condvar::new(),
+            },
+            value: Mutex::new(value),
+        });
+        num_tickets
+            .iter()
+            .enumerate()
+            .map(|(rotation, num_tickets)| {
+                Sequex::new((), *num_tickets)
+                    .into_iter()
+                    .map(|sequex| Self {
+                        sequex,
+                        rotation,
+                        shared: shared.clone(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the index of the rotation this ticket belongs to.
+    pub fn rotation(&self) -> usize {
+        self.rotation
+    }
+
+    /// Returns the position of this ticket in its rotation.
+    pub fn ticket(&self) -> u64 {
+        self.sequex.ticket()
+    }
+
+    /// Attempt to take this ticket's turn without blocking, then wait for the value, which
+    /// blocks while another rotation holds it or is chosen before this one. Returns
+    /// [SequexPoisoned] if this rotation or the value was poisoned.
+    pub fn try_lock(&self) -> Result<Option<RotationGuard<'_, T>>, SequexPoisoned> {
+        let Some(turn) = self.sequex.try_lock()? else {
+            return Ok(None);
+        };
+        self.acquire(turn).map(Some)
+    }
+
+    /// Take this ticket's turn, blocking until it comes, then wait for the value. Returns
+    /// [SequexPoisoned] if this rotation or the value was poisoned.
+    pub fn lock(&self) -> Result<RotationGuard<'_, T>, SequexPoisoned> {
+        let turn = self.sequex.lock()?;
+        self.acquire(turn)
+    }
+
+    fn acquire<'a>(&'a self, turn: Guard<'a, ()>) -> Result<RotationGuard<'a, T>, SequexPoisoned> {
+        let grant = self.shared.arbiter.acquire(self.rotation);
+        let value = self.shared.value.lock().map_err(|_| SequexPoisoned)?;
+        Ok(RotationGuard {
+            value,
+            _grant: grant,
+            turn,
+        })
+    }
+}
+
+impl<'a, T> RotationGuard<'a, T> {
+    /// Returns the cycle of its own rotation this turn belongs to. See [Guard::cycle].
+    pub fn cycle(&self) -> u64 {
+        self.turn.cycle()
+    }
+}
+
+impl<T> Deref for RotationGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for RotationGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl Arbiter {
+    // Block until the value is free and `rotation` is chosen to take it.
+    fn acquire(&self, rotation: usize) -> Grant<'_> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let arrival = state.arrivals;
+        state.arrivals += 1;
+        if let Some(waiting) = state.waiting.get_mut(rotation) {
+            *waiting = Some(arrival);
+        }
+        while state.held || self.choose(&state) != Some(rotation) {
+            state = self
+                .released
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        if let Some(waiting) = state.waiting.get_mut(rotation) {
+            *waiting = None;
+        }
+        state.held = true;
+        state.last = rotation;
+        Grant(self)
+    }
+
+    // Returns the waiting rotation that should take the value next.
+    fn choose(&self, state: &ArbiterState) -> Option<usize> {
+        let mut waiting = state
+            .waiting
+            .iter()
+            .enumerate()
+            .filter_map(|(rotation, arrival)| arrival.map(|arrival| (rotation, arrival)));
+        match self.arbitration {
+            Arbitration::FirstCome => waiting
+                .min_by_key(|(_, arrival)| *arrival)
+                .map(|(rotation, _)| rotation),
+            Arbitration::Alternate => {
+                let len = state.waiting.len();
+                waiting
+                    .min_by_key(|(rotation, _)| (rotation + len - state.last - 1) % len)
+                    .map(|(rotation, _)| rotation)
+            }
+            Arbitration::Priority => waiting.next().map(|(rotation, _)| rotation),
+        }
+    }
+}
+
+impl Drop for Grant<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.held = false;
+        drop(state);
+        self.0.released.notify_all();
+    }
+}
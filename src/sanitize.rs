@@ -0,0 +1,26 @@
+use crate::Shared;
+
+#[cfg(feature = "sanitize")]
+extern "C" {
+    fn __tsan_acquire(addr: *mut std::ffi::c_void);
+    fn __tsan_release(addr: *mut std::ffi::c_void);
+}
+
+impl<T> Shared<T> {
+    // Tell ThreadSanitizer that the lock was acquired, so that accesses to the value through
+    // the guard happen after those of the previous holder.
+    pub(crate) fn annotate_acquire(&self) {
+        #[cfg(feature = "sanitize")]
+        unsafe {
+            __tsan_acquire(self.value.get().cast())
+        }
+    }
+
+    // Tell ThreadSanitizer that the lock is about to be released.
+    pub(crate) fn annotate_release(&self) {
+        #[cfg(feature = "sanitize")]
+        unsafe {
+            __tsan_release(self.value.get().cast())
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{try_boxed_slice, BuildError, Sequex, Shared};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+// The earliest the next turn of each ticket may start, and whether any turn was ever
+// scheduled, so that unscheduled sequences take no lock to start a turn.
+pub(crate) struct Schedule {
+    used: AtomicBool,
+    starts: Box<[Mutex<Option<Instant>>]>,
+}
+
+impl Schedule {
+    pub(crate) fn new(len: usize) -> Result<Self, BuildError> {
+        Ok(Self {
+            used: AtomicBool::new(false),
+            starts: try_boxed_slice(len, || Mutex::new(None))?,
+        })
+    }
+
+    fn start(&self, ticket: u64) -> Option<MutexGuard<'_, Option<Instant>>> {
+        if !self.used.load(Ordering::SeqCst) {
+            return None;
+        }
+        let start = self.starts.get(ticket as usize)?;
+        Some(start.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl<T> Sequex<T> {
+    /// Hold this ticket's next turn until `at`. Once the turn comes, [Sequex::lock] waits
+    /// for both the turn and the schedule, and [Sequex::try_lock] returns `None` until `at`
+    /// has passed. The tickets after this one wait with it. The schedule applies to a single
+    /// turn, and is cleared once the turn starts.
+    pub fn schedule_at(&self, at: Instant) {
+        self.shared.schedule.used.store(true, Ordering::SeqCst);
+        if let Some(mut start) = self.shared.schedule.start(self.ticket) {
+            *start = Some(at);
+        }
+    }
+
+    /// Returns the time this ticket's next turn is held until, if it was scheduled with
+    /// [Sequex::schedule_at].
+    pub fn scheduled_at(&self) -> Option<Instant> {
+        *self.shared.schedule.start(self.ticket)?
+    }
+}
+
+impl<T> Shared<T> {
+    // Returns how long until `ticket`'s turn may start, if it is held up by its schedule or
+    // by the pacing of the sequence.
+    pub(crate) fn start_delay(&self, ticket: u64) -> Option<Duration> {
+        let scheduled = self
+            .schedule
+            .start(ticket)
+            .and_then(|start| *start)
+            .map(|start| start.saturating_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero());
+        scheduled.max(self.pace_remaining())
+    }
+
+    // Clear the schedule of `ticket`, as its turn has just started.
+    pub(crate) fn start_scheduled(&self, ticket: u64) {
+        if let Some(mut start) = self.schedule.start(ticket) {
+            start.take();
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{Guard, Sequex, SequexPoisoned, Status};
+use std::ops::DerefMut;
+
+/// The core operations of a ticket of a sequence, for application code that is generic over
+/// the lock, so tests can substitute a mock or deterministic implementation. Implemented by
+/// [Sequex].
+pub trait SequencedLock {
+    /// The value protected by the lock.
+    type Value;
+
+    /// The guard that holds the lock for a turn, releasing it when dropped.
+    type Guard<'a>: DerefMut<Target = Self::Value>
+    where
+        Self: 'a;
+
+    /// Attempt to take this ticket's turn without blocking. See [Sequex::try_lock].
+    fn try_lock(&self) -> Result<Option<Self::Guard<'_>>, SequexPoisoned>;
+
+    /// Take this ticket's turn, blocking until it comes. See [Sequex::lock].
+    fn lock(&self) -> Result<Self::Guard<'_>, SequexPoisoned>;
+
+    /// Wait for this ticket's turn and pass it on. See [Sequex::pass].
+    fn pass(&self) -> Result<(), SequexPoisoned>;
+
+    /// Returns the state of the sequence as seen by this ticket. See [Sequex::status].
+    fn status(&self) -> Status;
+}
+
+impl<T> SequencedLock for Sequex<T> {
+    type Value = T;
+    type Guard<'a>
+        = Guard<'a, T>
+    where
+        T: 'a;
+
+    fn try_lock(&self) -> Result<Option<Guard<'_, T>>, SequexPoisoned> {
+        Sequex::try_lock(self)
+    }
+
+    fn lock(&self) -> Result<Guard<'_, T>, SequexPoisoned> {
+        Sequex::lock(self)
+    }
+
+    fn pass(&self) -> Result<(), SequexPoisoned> {
+        Sequex::pass(self)
+    }
+
+    fn status(&self) -> Status {
+        Sequex::status(self)
+    }
+}
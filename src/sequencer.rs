@@ -0,0 +1,75 @@
+use crate::{Guard, Sequex, SequexPoisoned};
+use std::{cell::Cell, mem};
+
+/// A ticket of a sequence without a value, for ordering side effects that don't share data,
+/// such as forcing threads to print or commit in turn. [Sequencer::wait] blocks until it is
+/// this ticket's turn and holds it until [Sequencer::pass] hands it on, without a guard to
+/// keep alive in between.
+///
+/// A sequencer that is dropped leaves the rotation like [Sequencer::done], so the others
+/// carry on without it instead of the sequence being poisoned. A turn that is held when it is
+/// dropped is passed on.
+pub struct Sequencer {
+    sequex: Sequex<()>,
+    held: Cell<bool>,
+}
+
+impl Sequencer {
+    /// Create the `num_tickets` sequencers of a new sequence.
+    pub fn new(num_tickets: u64) -> Vec<Self> {
+        Sequex::builder((), num_tickets)
+            .poisoning(false)
+            .build()
+            .into_iter()
+            .map(|sequex| Self {
+                sequex,
+                held: Cell::new(false),
+            })
+            .collect()
+    }
+
+    /// Returns the position of this sequencer in the sequence.
+    pub fn ticket(&self) -> u64 {
+        self.sequex.ticket()
+    }
+
+    /// Block until it is this sequencer's turn, and hold it until [Sequencer::pass]. Returns
+    /// immediately if the turn is already held. Returns [SequexPoisoned] if the sequence was
+    /// poisoned.
+    pub fn wait(&self) -> Result<(), SequexPoisoned> {
+        if !self.held.get() {
+            mem::forget(self.sequex.lock()?);
+            self.held.set(true);
+        }
+        Ok(())
+    }
+
+    /// Hand the turn on to the next sequencer, waiting for it first if it isn't held yet.
+    /// Returns [SequexPoisoned] if the sequence was poisoned.
+    pub fn pass(&self) -> Result<(), SequexPoisoned> {
+        self.wait()?;
+        self.release();
+        Ok(())
+    }
+
+    /// Leave the rotation for good, handing on the turn if it is held, so the remaining
+    /// sequencers take their turns without this one.
+    pub fn done(self) {
+        drop(self);
+    }
+
+    fn release(&self) {
+        if self.held.replace(false) {
+            drop(Guard {
+                sequex: &self.sequex,
+                mutated: false,
+            });
+        }
+    }
+}
+
+impl Drop for Sequencer {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{Guard, Sequex, SequexPoisoned};
+use std::ops::{Deref, DerefMut};
+
+/// A sequence-mutex lock over a fixed set of values. Every turn locks all of the values at
+/// once, so they advance through the ticket order together and can never be observed at
+/// different cycles.
+pub struct SequexSet<T> {
+    sequex: Sequex<Box<[T]>>,
+}
+
+/// An RAII guard over every value of a [SequexSet], released when dropped.
+pub struct SetGuard<'a, T> {
+    guard: Guard<'a, Box<[T]>>,
+}
+
+impl<T> SequexSet<T> {
+    /// Create a new sequence that wraps a set of values.
+    pub fn new(values: impl IntoIterator<Item = T>, num_tickets: u64) -> Vec<Self> {
+        let values = values.into_iter().collect();
+        Sequex::new(values, num_tickets)
+            .into_iter()
+            .map(|sequex| Self { sequex })
+            .collect()
+    }
+
+    /// Attempt to lock every value in the set. Does not block the current thread if the lock
+    /// could not be acquired. Returns [SequexPoisoned] if the lock was poisoned.
+    pub fn try_lock(&self) -> Result<Option<SetGuard<'_, T>>, SequexPoisoned> {
+        Ok(self.sequex.try_lock()?.map(|guard| SetGuard { guard }))
+    }
+
+    /// Lock every value in the set, blocking the current thread if it could not be acquired.
+    /// Returns [SequexPoisoned] if the lock was poisoned.
+    pub fn lock(&self) -> Result<SetGuard<'_, T>, SequexPoisoned> {
+        self.sequex.lock().map(|guard| SetGuard { guard })
+    }
+}
+
+impl<'a, T> SetGuard<'a, T> {
+    /// Returns the cycle that every value in the set was locked at.
+    pub fn cycle(&self) -> u64 {
+        self.guard.cycle()
+    }
+}
+
+impl<'a, T> Deref for SetGuard<'a, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<'a, T> DerefMut for SetGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
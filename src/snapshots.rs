@@ -0,0 +1,85 @@
+use crate::{is_live, Sequex, Shared};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::Sender,
+    Mutex, PoisonError,
+};
+
+// Sends a snapshot of the value at the end of a cycle, returning false once the receiver is
+// gone.
+type Subscriber<T> = Box<dyn Fn(u64, &T) -> bool + Send>;
+
+// Subscribers to the value at the end of every cycle. Nothing is cloned until a subscriber
+// is first registered.
+pub(crate) struct SnapshotSubscribers<T> {
+    used: AtomicBool,
+    subscribers: Mutex<Vec<Subscriber<T>>>,
+}
+
+impl<T: Clone + Send + 'static> Sequex<T> {
+    /// Send a clone of the value on `sender`, along with the number of the cycle, each time a
+    /// cycle completes, so downstream consumers can process a consistent series of states
+    /// without taking part in the rotation. Every cycle completed after subscribing is sent
+    /// exactly once and in order, as the value was left by its last turn, before any swaps
+    /// requested by a [Controller](crate::Controller) are applied. The clone is taken by the
+    /// thread completing the cycle while it holds the lock. The subscription ends when the
+    /// receiver is dropped, and once the sequence is poisoned or finishes, after which the
+    /// receiver is disconnected.
+    pub fn subscribe_snapshots(&self, sender: Sender<(u64, T)>) {
+        let send = move |cycle, value: &T| sender.send((cycle, value.clone())).is_ok();
+        self.shared.subscribe_snapshots(Box::new(send));
+    }
+}
+
+impl<T> Default for SnapshotSubscribers<T> {
+    fn default() -> Self {
+        Self {
+            used: AtomicBool::new(false),
+            subscribers: Mutex::default(),
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    // The subscriber is dropped straight away if the sequence already ended, which is checked
+    // under the registry lock so that it can't miss `end_snapshots`.
+    fn subscribe_snapshots(&self, subscriber: Subscriber<T>) {
+        self.snapshot_subscribers.used.store(true, Ordering::SeqCst);
+        let mut subscribers = self
+            .snapshot_subscribers
+            .subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if is_live(self.current.load(Ordering::SeqCst)) {
+            subscribers.push(subscriber);
+        }
+    }
+
+    // Send the value to the snapshot subscribers as the current cycle completes, dropping any
+    // that stopped listening. Must only be called while holding the lock.
+    pub(crate) fn send_snapshots(&self) {
+        if !self.snapshot_subscribers.used.load(Ordering::SeqCst)
+            || !self.initialized.load(Ordering::SeqCst)
+        {
+            return;
+        }
+        let cycle = self.cycle.load(Ordering::SeqCst);
+        let value = unsafe { (*self.value.get()).assume_init_ref() };
+        self.snapshot_subscribers
+            .subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|send| send(cycle, value));
+    }
+
+    // Disconnect the snapshot subscribers of a sequence that ended.
+    pub(crate) fn end_snapshots(&self) {
+        if self.snapshot_subscribers.used.load(Ordering::SeqCst) {
+            self.snapshot_subscribers
+                .subscribers
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clear();
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{Sequex, SequexPoisoned};
+use std::sync::atomic::Ordering;
+
+impl<T: Clone> Sequex<T> {
+    /// Run `compute` speculatively against a snapshot of the value, without waiting for this
+    /// ticket's turn, then wait for the turn and pass the result to `commit` along with the
+    /// value. If another turn accessed the value mutably since the snapshot was taken, as
+    /// tracked by its [version](crate::Guard::version), the speculative result is discarded
+    /// and `compute` is run again on the current value before committing, so the committed
+    /// result is always the one a plain turn would have produced. Returns [SequexPoisoned] if
+    /// the lock was poisoned or the sequence has finished.
+    pub fn speculate<R, U>(
+        &self,
+        mut compute: impl FnMut(&T) -> R,
+        commit: impl FnOnce(&mut T, R) -> U,
+    ) -> Result<U, SequexPoisoned> {
+        let (snapshot, version) = self.shared.versioned_snapshot()?;
+        let speculated = compute(&snapshot);
+        drop(snapshot);
+        let mut guard = self.lock()?;
+        let result = if self.shared.version.load(Ordering::SeqCst) == version {
+            speculated
+        } else {
+            compute(&guard)
+        };
+        Ok(commit(&mut guard, result))
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{wait::MAX_SPINS, Guard, Sequex, SequexPoisoned};
+use std::{
+    hint,
+    time::{Duration, Instant},
+};
+
+impl<T> Sequex<T> {
+    /// Attempt to acquire the lock up to `attempts` times, busy-waiting between attempts
+    /// without parking the thread, then give up. Returns `None` if the turn didn't come in
+    /// time, and [SequexPoisoned] if the lock was poisoned or the sequence has finished.
+    pub fn try_lock_spin(&self, attempts: u32) -> Result<Option<Guard<'_, T>>, SequexPoisoned> {
+        let mut spins = 1;
+        for _ in 0..attempts {
+            if let Some(guard) = self.try_lock()? {
+                return Ok(Some(guard));
+            }
+            spins = spin(spins);
+        }
+        Ok(None)
+    }
+
+    /// Busy-wait for this ticket's turn for up to `budget`, then give up, for latency
+    /// sensitive callers that would rather fall back to other work than park the thread.
+    /// Returns `None` if the turn didn't come in time, and [SequexPoisoned] if the lock was
+    /// poisoned or the sequence has finished.
+    pub fn try_lock_spin_for(
+        &self,
+        budget: Duration,
+    ) -> Result<Option<Guard<'_, T>>, SequexPoisoned> {
+        let start = Instant::now();
+        let mut spins = 1;
+        loop {
+            if let Some(guard) = self.try_lock()? {
+                return Ok(Some(guard));
+            }
+            if start.elapsed() >= budget {
+                return Ok(None);
+            }
+            spins = spin(spins);
+        }
+    }
+}
+
+// Issue `spins` spin loop hints, returning how many to issue before the next attempt, backing
+// off up to as many as `WaitStrategy::Spin` does.
+fn spin(spins: u32) -> u32 {
+    for _ in 0..spins {
+        hint::spin_loop();
+    }
+    (spins * 2).min(MAX_SPINS)
+}
@@ -0,0 +1,95 @@
+use crate::{Sequex, FINISHED, LOCKED, MOVED, POISON};
+use std::sync::atomic::Ordering;
+
+/// The state of a sequence as seen by one of its tickets. Returned by [Sequex::status].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Status {
+    /// It is this ticket's turn, and the lock is free.
+    Ready,
+    /// It is another ticket's turn, and the lock is free.
+    Waiting,
+    /// A ticket holds the lock.
+    Locked,
+    /// The sequence was poisoned.
+    Poisoned,
+    /// A one-shot sequence has completed its only cycle.
+    Finished,
+    /// The value was handed to a successor sequence.
+    Moved,
+}
+
+/// Why a sequence was poisoned, returned by [Sequex::poison_cause].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoisonCause {
+    ticket: Option<u64>,
+    reason: PoisonReason,
+}
+
+/// What poisoned a sequence. See [PoisonCause].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PoisonReason {
+    /// The holder of the lock panicked, or the lazy initializer of the value did.
+    Panicked,
+    /// A ticket was dropped.
+    TicketDropped,
+    /// A ticket called [Sequex::poison], or foreign code poisoned it through the C API.
+    Requested,
+    /// A turn overran its time slice under [OverrunPolicy::Poison](crate::OverrunPolicy).
+    Overrun,
+    /// A fault injected by the `test-util` feature.
+    Injected,
+    /// The state of the sequence was found changed under the holder of the lock, as it
+    /// released it.
+    Corrupted,
+}
+
+impl PoisonCause {
+    pub(crate) fn new(ticket: Option<u64>, reason: PoisonReason) -> Self {
+        Self { ticket, reason }
+    }
+
+    /// Returns the ticket that poisoned the sequence, or `None` if it was its lazy
+    /// initializer.
+    pub fn ticket(&self) -> Option<u64> {
+        self.ticket
+    }
+
+    /// Returns what poisoned the sequence.
+    pub fn reason(&self) -> PoisonReason {
+        self.reason
+    }
+}
+
+impl<T> Sequex<T> {
+    /// Returns true if the sequence was poisoned, without locking.
+    pub fn is_poisoned(&self) -> bool {
+        self.shared.current.load(Ordering::SeqCst) == POISON
+    }
+
+    /// Returns true if the sequence ended without being poisoned, ie. a one-shot sequence
+    /// completed or the value was handed to a successor, without locking.
+    pub fn is_finished(&self) -> bool {
+        matches!(self.shared.current.load(Ordering::SeqCst), FINISHED | MOVED)
+    }
+
+    /// Returns which ticket poisoned the sequence and why, or `None` if it wasn't poisoned, so
+    /// health checks can report it without attempting to lock.
+    pub fn poison_cause(&self) -> Option<PoisonCause> {
+        self.shared.poison_cause.get().copied()
+    }
+
+    /// Returns the state of the sequence as seen by this ticket. The state may have changed
+    /// by the time it is inspected.
+    pub fn status(&self) -> Status {
+        match self.shared.current.load(Ordering::SeqCst) {
+            LOCKED => Status::Locked,
+            POISON => Status::Poisoned,
+            MOVED => Status::Moved,
+            FINISHED => Status::Finished,
+            current if current == self.ticket => Status::Ready,
+            _ => Status::Waiting,
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! Utilities for testing code that takes part in a sequence, without real threads or sleeps.
+//! Enabled with the `test-util` feature.
+//!
+//! A [TestScheduler] owns every ticket of a sequence and steps through turns on the current
+//! thread, so a test can put the sequence into a known state ("advance to ticket 2"), hand a
+//! ticket to the code under test, and simulate tickets timing out against a virtual clock.
+//! A [FaultInjector] attached to a sequence injects poisoning, delays and spurious failures,
+//! to exercise the error handling of code built on it. [Interleaving] generates reproducible
+//! random schedules from a seed, and an [InvariantChecker] verifies that turns never overlap
+//! and are taken in order.
+
+use crate::{is_live, Sequex, SequexPoisoned, Shared, FINISHED};
+use std::{
+    collections::HashMap,
+    sync::{atomic::Ordering, Arc, Mutex, MutexGuard, PoisonError},
+    thread,
+    time::Duration,
+};
+
+/// A deterministic scheduler that drives the turns of a sequence from the current thread.
+pub struct TestScheduler<T> {
+    tickets: Vec<Option<Sequex<T>>>,
+    shared: Option<Arc<Shared<T>>>,
+    now: Duration,
+}
+
+impl<T> TestScheduler<T> {
+    /// Create a scheduler over a new sequence with the default configuration.
+    pub fn new(value: T, num_tickets: u64) -> Self {
+        Self::from_tickets(Sequex::new(value, num_tickets))
+    }
+
+    /// Create a scheduler over the tickets of a sequence, for example one configured with a
+    /// [Builder](crate::Builder). The tickets must be in order, as they were created.
+    pub fn from_tickets(tickets: Vec<Sequex<T>>) -> Self {
+        Self {
+            shared: tickets.first().map(|sequex| sequex.shared.clone()),
+            tickets: tickets.into_iter().map(Some).collect(),
+            now: Duration::ZERO,
+        }
+    }
+
+    /// Returns a ticket, to pass to the code under test. Returns `None` if the ticket has
+    /// been taken or timed out.
+    pub fn ticket(&self, ticket: u64) -> Option<&Sequex<T>> {
+        self.tickets.get(ticket as usize)?.as_ref()
+    }
+
+    /// Take a ticket out of the scheduler, to move it into the code under test.
+    pub fn take_ticket(&mut self, ticket: u64) -> Option<Sequex<T>> {
+        self.tickets.get_mut(ticket as usize)?.take()
+    }
+
+    /// Returns the ticket whose turn it is, or `None` if the lock is held or the sequence has
+    /// ended.
+    pub fn current(&self) -> Option<u64> {
+        self.state().filter(|&current| current < FINISHED)
+    }
+
+    /// Take the current turn and run `f` on the value. Returns the ticket that took the turn,
+    /// or `None` if the turn can't be taken, because the lock is held, the ticket was taken
+    /// out of the scheduler, or the sequence is waiting on its frame. Returns
+    /// [SequexPoisoned] if the lock was poisoned or the sequence has finished.
+    pub fn step(&mut self, f: impl FnOnce(&mut T)) -> Result<Option<u64>, SequexPoisoned> {
+        let Some(ticket) = self.state().filter(|&current| is_live(current)) else {
+            return Err(SequexPoisoned);
+        };
+        // A held lock is not a ticket the scheduler owns.
+        let Some(Some(sequex)) = self.tickets.get(ticket as usize) else {
+            return Ok(None);
+        };
+        Ok(sequex.try_lock()?.map(|mut guard| {
+            f(&mut guard);
+            ticket
+        }))
+    }
+
+    /// Pass turns without touching the value until it is `ticket`'s turn. Returns
+    /// [SequexPoisoned] if the sequence ends first, or the turn can't be reached within a
+    /// cycle because a turn can't be taken or `ticket` was retired.
+    pub fn advance_to(&mut self, ticket: u64) -> Result<(), SequexPoisoned> {
+        for _ in 0..self.tickets.len() {
+            if self.current() == Some(ticket) {
+                return Ok(());
+            }
+            if self.step(|_| ())?.is_none() {
+                break;
+            }
+        }
+        if self.current() == Some(ticket) {
+            Ok(())
+        } else {
+            Err(SequexPoisoned)
+        }
+    }
+
+    /// Simulate the ticket whose turn it is timing out: the virtual clock advances by
+    /// `timeout` and the ticket is dropped, which poisons the sequence or, without poisoning,
+    /// retires the ticket. Returns the ticket that timed out, or `None` if there is no current
+    /// turn.
+    pub fn time_out(&mut self, timeout: Duration) -> Option<u64> {
+        self.advance_clock(timeout);
+        let ticket = self.current()?;
+        drop(self.take_ticket(ticket));
+        Some(ticket)
+    }
+
+    /// Returns the time on the virtual clock.
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Advance the virtual clock.
+    pub fn advance_clock(&mut self, duration: Duration) {
+        self.now = self.now.saturating_add(duration);
+    }
+
+    fn state(&self) -> Option<u64> {
+        Some(self.shared.as_ref()?.current.load(Ordering::SeqCst))
+    }
+}
+
+/// A set of faults to inject into the sequences it is attached to with
+/// [Builder::fault_injector](crate::Builder::fault_injector). Faults can be added at any time,
+/// from any clone of the injector.
+#[derive(Clone, Default)]
+pub struct FaultInjector {
+    faults: Arc<Mutex<HashMap<u64, Faults>>>,
+}
+
+// The faults injected into one ticket.
+#[derive(Default)]
+struct Faults {
+    failed_try_locks: u64,
+    delay_before: Duration,
+    delay_after: Duration,
+    poison_at: Option<u64>,
+}
+
+impl FaultInjector {
+    /// Create an injector without any faults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make the next `count` attempts of `ticket` to take its turn fail spuriously, as if the
+    /// turn hadn't come yet. Blocking locks retry until the failures are used up.
+    pub fn fail_try_locks(&self, ticket: u64, count: u64) {
+        self.faults().entry(ticket).or_default().failed_try_locks = count;
+    }
+
+    /// Sleep for `delay` at the start of each of `ticket`'s turns, once it holds the lock.
+    pub fn delay_before(&self, ticket: u64, delay: Duration) {
+        self.faults().entry(ticket).or_default().delay_before = delay;
+    }
+
+    /// Sleep for `delay` at the end of each of `ticket`'s turns, before the turn is passed on.
+    pub fn delay_after(&self, ticket: u64, delay: Duration) {
+        self.faults().entry(ticket).or_default().delay_after = delay;
+    }
+
+    /// Poison the sequence when `ticket` takes its turn in `cycle`, instead of giving it the
+    /// lock.
+    pub fn poison_at(&self, ticket: u64, cycle: u64) {
+        self.faults().entry(ticket).or_default().poison_at = Some(cycle);
+    }
+
+    /// Remove every fault.
+    pub fn clear(&self) {
+        self.faults().clear();
+    }
+
+    fn faults(&self) -> MutexGuard<'_, HashMap<u64, Faults>> {
+        self.faults.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Returns true if an attempt by `ticket` to take its turn should fail spuriously.
+    pub(crate) fn fail_try_lock(&self, ticket: u64) -> bool {
+        self.faults().get_mut(&ticket).is_some_and(|faults| {
+            let fail = faults.failed_try_locks > 0;
+            faults.failed_try_locks = faults.failed_try_locks.saturating_sub(1);
+            fail
+        })
+    }
+
+    // Returns true if `ticket` taking its turn in `cycle` should poison the sequence.
+    pub(crate) fn poisons(&self, ticket: u64, cycle: u64) -> bool {
+        self.faults()
+            .get(&ticket)
+            .is_some_and(|faults| faults.poison_at == Some(cycle))
+    }
+
+    // Sleep at the start of one of `ticket`'s turns.
+    pub(crate) fn before_turn(&self, ticket: u64) {
+        let delay = self.faults().get(&ticket).map(|faults| faults.delay_before);
+        delay.into_iter().for_each(thread::sleep);
+    }
+
+    // Sleep at the end of one of `ticket`'s turns.
+    pub(crate) fn after_turn(&self, ticket: u64) {
+        let delay = self.faults().get(&ticket).map(|faults| faults.delay_after);
+        delay.into_iter().for_each(thread::sleep);
+    }
+}
+
+/// A random schedule for exercising a sequence: a ticket count, a number of cycles, how long
+/// each turn holds the lock and the order in which tickets are dropped. Generated from a seed,
+/// so a failing schedule can be reproduced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Interleaving {
+    num_tickets: u64,
+    cycles: u64,
+    holds: Vec<u32>,
+    drop_order: Vec<u64>,
+}
+
+impl Interleaving {
+    /// Generate a schedule of one to eight tickets over one to four cycles from `seed`.
+    pub fn from_seed(seed: u64) -> Self {
+        let mut rng = Rng(seed ^ 0x9e37_79b9_7f4a_7c15);
+        let num_tickets = rng.below(8) + 1;
+        let cycles = rng.below(4) + 1;
+        let holds = (0..num_tickets * cycles)
+            .map(|_| rng.below(4) as u32)
+            .collect();
+        let mut drop_order: Vec<u64> = (0..num_tickets).collect();
+        for i in (1..drop_order.len()).rev() {
+            drop_order.swap(i, rng.below(i as u64 + 1) as usize);
+        }
+        Self {
+            num_tickets,
+            cycles,
+            holds,
+            drop_order,
+        }
+    }
+
+    /// Returns the number of tickets in the sequence.
+    pub fn num_tickets(&self) -> u64 {
+        self.num_tickets
+    }
+
+    /// Returns the number of cycles each ticket takes a turn in.
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// Returns how many times `ticket` should yield the thread while holding the lock in
+    /// `cycle`, to perturb the interleaving.
+    pub fn hold(&self, ticket: u64, cycle: u64) -> u32 {
+        let index = cycle * self.num_tickets + ticket;
+        self.holds.get(index as usize).copied().unwrap_or(0)
+    }
+
+    /// Returns the order in which tickets should be dropped once they are done.
+    pub fn drop_order(&self) -> &[u64] {
+        &self.drop_order
+    }
+}
+
+// A xorshift generator, which is plenty for shuffling schedules.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound.max(1)
+    }
+}
+
+/// A checker of the invariants of a sequence, for verifying the sequence or a wrapper around
+/// it. The code under test reports each turn with [InvariantChecker::enter] and
+/// [InvariantChecker::exit], and the checker records every turn that overlaps another or is
+/// taken out of order.
+pub struct InvariantChecker {
+    state: Mutex<CheckerState>,
+}
+
+struct CheckerState {
+    num_tickets: u64,
+    holder: Option<u64>,
+    next: u64,
+    retired: Vec<bool>,
+    turns: u64,
+    violations: Vec<Violation>,
+}
+
+/// A broken invariant recorded by an [InvariantChecker].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Violation {
+    /// A ticket entered its turn while another ticket held the lock.
+    Overlap {
+        /// The ticket holding the lock.
+        holder: u64,
+        /// The ticket that entered.
+        entered: u64,
+    },
+    /// A ticket entered its turn when it was another ticket's turn.
+    OutOfOrder {
+        /// The ticket whose turn it was.
+        expected: u64,
+        /// The ticket that entered.
+        entered: u64,
+    },
+    /// A ticket exited a turn it did not hold.
+    NotHolder {
+        /// The ticket that exited.
+        ticket: u64,
+    },
+}
+
+impl InvariantChecker {
+    /// Create a checker for a sequence of `num_tickets` tickets, starting at the first ticket.
+    pub fn new(num_tickets: u64) -> Self {
+        Self {
+            state: Mutex::new(CheckerState {
+                num_tickets,
+                holder: None,
+                next: 0,
+                retired: (0..num_tickets).map(|_| false).collect(),
+                turns: 0,
+                violations: Vec::new(),
+            }),
+        }
+    }
+
+    /// Record that `ticket` has taken its turn.
+    pub fn enter(&self, ticket: u64) {
+        let mut state = self.state();
+        if let Some(holder) = state.holder {
+            state.violations.push(Violation::Overlap {
+                holder,
+                entered: ticket,
+            });
+        }
+        let expected = state.expected();
+        if expected != ticket {
+            state.violations.push(Violation::OutOfOrder {
+                expected,
+                entered: ticket,
+            });
+        }
+        state.holder = Some(ticket);
+        state.next = ticket + 1;
+        state.turns += 1;
+    }
+
+    /// Record that `ticket` has released its turn.
+    pub fn exit(&self, ticket: u64) {
+        let mut state = self.state();
+        if state.holder != Some(ticket) {
+            state.violations.push(Violation::NotHolder { ticket });
+        }
+        state.holder = None;
+    }
+
+    /// Record that `ticket` was retired from a sequence without poisoning, so its turns are
+    /// skipped.
+    pub fn retire(&self, ticket: u64) {
+        if let Some(retired) = self.state().retired.get_mut(ticket as usize) {
+            *retired = true;
+        }
+    }
+
+    /// Returns the number of turns that were entered.
+    pub fn turns(&self) -> u64 {
+        self.state().turns
+    }
+
+    /// Returns every violation recorded so far.
+    pub fn violations(&self) -> Vec<Violation> {
+        self.state().violations.clone()
+    }
+
+    /// Returns the first violation recorded, if any.
+    pub fn check(&self) -> Result<(), Violation> {
+        match self.state().violations.first() {
+            Some(violation) => Err(*violation),
+            None => Ok(()),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, CheckerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl CheckerState {
+    // Returns the ticket whose turn is next, skipping retired tickets.
+    fn expected(&self) -> u64 {
+        let mut next = self.next % self.num_tickets.max(1);
+        for _ in 0..self.num_tickets {
+            if !self.retired.get(next as usize).copied().unwrap_or(false) {
+                break;
+            }
+            next = (next + 1) % self.num_tickets;
+        }
+        next
+    }
+}
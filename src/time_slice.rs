@@ -0,0 +1,170 @@
+use crate::{PoisonCause, PoisonReason, Shared};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+/// A turn that was held for longer than the time slice of its sequence, reported to the
+/// callback given to [Builder::time_slice](crate::Builder::time_slice).
+#[derive(Clone, Debug)]
+pub struct Overrun {
+    ticket: u64,
+    cycle: u64,
+    held_for: Duration,
+}
+
+/// What to do about a turn that overran its time slice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OverrunPolicy {
+    /// Let the turn carry on.
+    Warn,
+    /// Let the turn carry on, but once its holder releases it, skip the turns of the tickets
+    /// queued behind it for the rest of the cycle, passing the turn straight to the first
+    /// ticket of the next cycle. A turn can't be taken away from its holder while it has
+    /// mutable access to the value, so this is how a sequence catches up with the time an
+    /// overrun cost it.
+    Pass,
+    /// Poison the sequence, waking every waiting ticket with
+    /// [SequexPoisoned](crate::SequexPoisoned). The holder keeps its lock until its guard is
+    /// dropped.
+    Poison,
+}
+
+// The longest a turn may be held, and the turn that is currently held.
+pub(crate) struct TimeSlice {
+    max: Duration,
+    on_overrun: Arc<dyn Fn(&Overrun) -> OverrunPolicy + Send + Sync>,
+    turn: Mutex<Option<Turn>>,
+    // Whether the held turn overran with `OverrunPolicy::Pass`.
+    pass: AtomicBool,
+}
+
+struct Turn {
+    ticket: u64,
+    cycle: u64,
+    since: Instant,
+    reported: bool,
+}
+
+impl Overrun {
+    /// Returns the ticket that overran its time slice.
+    pub fn ticket(&self) -> u64 {
+        self.ticket
+    }
+
+    /// Returns the cycle of the turn that overran.
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+    /// Returns how long the turn had been held when the overrun was detected.
+    pub fn held_for(&self) -> Duration {
+        self.held_for
+    }
+}
+
+impl TimeSlice {
+    pub(crate) fn new(
+        max: Duration,
+        on_overrun: impl Fn(&Overrun) -> OverrunPolicy + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            max,
+            on_overrun: Arc::new(on_overrun),
+            turn: Mutex::new(None),
+            pass: AtomicBool::new(false),
+        }
+    }
+
+    // The same time slice, for a successor of the sequence.
+    pub(crate) fn renewed(&self) -> Self {
+        Self {
+            max: self.max,
+            on_overrun: self.on_overrun.clone(),
+            turn: Mutex::new(None),
+            pass: AtomicBool::new(false),
+        }
+    }
+
+    fn turn(&self) -> MutexGuard<'_, Option<Turn>> {
+        self.turn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Report the held turn if it has overrun and hasn't been reported yet, returning the
+    // policy chosen by the callback.
+    fn report(&self, turn: Option<&mut Turn>) -> Option<OverrunPolicy> {
+        let turn = turn?;
+        let held_for = turn.since.elapsed();
+        if turn.reported || held_for <= self.max {
+            return None;
+        }
+        turn.reported = true;
+        let policy = (self.on_overrun)(&Overrun {
+            ticket: turn.ticket,
+            cycle: turn.cycle,
+            held_for,
+        });
+        if policy == OverrunPolicy::Pass {
+            self.pass.store(true, Ordering::SeqCst);
+        }
+        Some(policy)
+    }
+}
+
+impl<T> Shared<T> {
+    // Start timing the turn `ticket` has just acquired.
+    pub(crate) fn start_slice(&self, ticket: u64) {
+        if let Some(slice) = &self.config.time_slice {
+            slice.pass.store(false, Ordering::SeqCst);
+            *slice.turn() = Some(Turn {
+                ticket,
+                cycle: self.cycle.load(Ordering::SeqCst),
+                since: Instant::now(),
+                reported: false,
+            });
+        }
+    }
+
+    // Check whether the held turn has overrun its time slice, applying the policy if it has.
+    pub(crate) fn check_slice(&self) {
+        if let Some(slice) = &self.config.time_slice {
+            let policy = slice.report(slice.turn().as_mut());
+            if policy == Some(OverrunPolicy::Poison) {
+                self.poison_overrun();
+            }
+        }
+    }
+
+    // Stop timing the held turn as it ends, reporting it if it overran. Returns true if the
+    // sequence was poisoned as a result.
+    pub(crate) fn end_slice(&self) -> bool {
+        let Some(slice) = &self.config.time_slice else {
+            return false;
+        };
+        let mut turn = slice.turn().take();
+        if slice.report(turn.as_mut()) == Some(OverrunPolicy::Poison) {
+            self.poison_overrun();
+            return true;
+        }
+        false
+    }
+
+    // Returns true if the turn that just ended overran with `OverrunPolicy::Pass`, so that
+    // the rest of its cycle is skipped.
+    pub(crate) fn slice_passed(&self) -> bool {
+        self.config
+            .time_slice
+            .as_ref()
+            .is_some_and(|slice| slice.pass.load(Ordering::SeqCst))
+    }
+
+    // Poison the sequence for the held turn overrunning its time slice.
+    fn poison_overrun(&self) {
+        let ticket = self.serving.load(Ordering::Relaxed);
+        self.poison(PoisonCause::new(Some(ticket), PoisonReason::Overrun));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{wait::block, SequexPoisoned, WaitStrategy};
+use std::{
+    collections::BTreeMap,
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+};
+
+/// A lock that is acquired in the order of logical timestamps instead of a fixed set of
+/// tickets, for schedules that are only known as they unfold, like the event times of a
+/// discrete-event simulation. Timestamps need not be dense, and are served in increasing
+/// order: a [Timestamp] can only lock once every earlier timestamp that was scheduled has been
+/// served. Timestamps that are equal are served one at a time, in no particular order.
+pub struct Timeline<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// A scheduled point on a [Timeline], which may lock the value once when its time comes.
+/// Dropping a timestamp that hasn't locked the value cancels it.
+pub struct Timestamp<T> {
+    inner: Arc<Inner<T>>,
+    time: u64,
+    served: AtomicBool,
+}
+
+/// An RAII guard of a [Timeline] that releases the lock when dropped.
+pub struct TimelineGuard<'a, T> {
+    value: MutexGuard<'a, T>,
+    inner: &'a Inner<T>,
+    time: u64,
+}
+
+struct Inner<T> {
+    state: Mutex<State>,
+    value: Mutex<T>,
+}
+
+// The timestamps that are waiting to be served, by the number scheduled at each time.
+struct State {
+    pending: BTreeMap<u64, usize>,
+    floor: u64,
+    next: u64,
+    held: bool,
+}
+
+impl<T> Timeline<T> {
+    /// Create a new timeline that wraps a value, starting at time zero.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    pending: BTreeMap::new(),
+                    floor: 0,
+                    next: 0,
+                    held: false,
+                }),
+                value: Mutex::new(value),
+            }),
+        }
+    }
+
+    /// Schedule a timestamp at `time`. Returns `None` if `time` is earlier than a timestamp
+    /// that is holding the lock or has already been served.
+    pub fn schedule(&self, time: u64) -> Option<Timestamp<T>> {
+        self.inner.insert(time).then(|| Timestamp {
+            inner: self.inner.clone(),
+            time,
+            served: AtomicBool::new(false),
+        })
+    }
+
+    // Schedule a timestamp after every one that was scheduled so far.
+    #[cfg(feature = "pipeline")]
+    pub(crate) fn schedule_next(&self) -> Timestamp<T> {
+        let mut state = self.inner.state();
+        let time = state.next;
+        state.insert(time);
+        Timestamp {
+            inner: self.inner.clone(),
+            time,
+            served: AtomicBool::new(false),
+        }
+    }
+
+    /// Schedule a timestamp at `time` and acquire the lock on it, blocking the current thread
+    /// until every earlier timestamp has been served. Returns `None` if `time` is earlier than
+    /// a timestamp that is holding the lock or has already been served, and [SequexPoisoned]
+    /// if a holder of the lock panicked.
+    pub fn lock_at(&self, time: u64) -> Result<Option<TimelineGuard<'_, T>>, SequexPoisoned> {
+        if !self.inner.insert(time) {
+            return Ok(None);
+        }
+        match block(WaitStrategy::default(), None, None, || {
+            self.inner.try_acquire(time)
+        }) {
+            Ok(guard) => Ok(Some(guard)),
+            Err(error) => {
+                self.inner.state().remove(time);
+                Err(error)
+            }
+        }
+    }
+
+    /// Returns the earliest time that can still be scheduled, which is the time of the
+    /// timestamp holding the lock or the latest one served.
+    pub fn now(&self) -> u64 {
+        self.inner.state().floor
+    }
+}
+
+impl<T> Timestamp<T> {
+    /// Returns the time this timestamp was scheduled at.
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    /// Attempt to acquire the lock, without blocking the current thread if an earlier
+    /// timestamp has yet to be served. Returns [SequexPoisoned] if a holder of the lock
+    /// panicked, or this timestamp has already locked the value.
+    pub fn try_lock(&self) -> Result<Option<TimelineGuard<'_, T>>, SequexPoisoned> {
+        if self.served.load(Ordering::SeqCst) {
+            return Err(SequexPoisoned);
+        }
+        let guard = self.inner.try_acquire(self.time)?;
+        if guard.is_some() {
+            self.served.store(true, Ordering::SeqCst);
+        }
+        Ok(guard)
+    }
+
+    /// Acquire the lock, blocking the current thread until every earlier timestamp has been
+    /// served. Returns [SequexPoisoned] if a holder of the lock panicked, or this timestamp
+    /// has already locked the value.
+    pub fn lock(&self) -> Result<TimelineGuard<'_, T>, SequexPoisoned> {
+        block(WaitStrategy::default(), None, None, || self.try_lock())
+    }
+}
+
+impl<T> Inner<T> {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Add a pending timestamp at `time`, unless it is already in the past.
+    fn insert(&self, time: u64) -> bool {
+        let mut state = self.state();
+        if time < state.floor {
+            return false;
+        }
+        state.insert(time);
+        true
+    }
+
+    fn try_acquire(&self, time: u64) -> Result<Option<TimelineGuard<'_, T>>, SequexPoisoned> {
+        let mut state = self.state();
+        let earliest = state.pending.keys().next().copied();
+        if state.held || earliest.is_some_and(|earliest| earliest < time) {
+            return Ok(None);
+        }
+        // The value is locked before the turn is marked held, so that a poisoned value fails
+        // every timestamp instead of leaving the timeline held forever.
+        let value = self.value.lock().map_err(|_| SequexPoisoned)?;
+        state.held = true;
+        state.floor = time;
+        drop(state);
+        Ok(Some(TimelineGuard {
+            value,
+            inner: self,
+            time,
+        }))
+    }
+}
+
+impl State {
+    fn insert(&mut self, time: u64) {
+        *self.pending.entry(time).or_default() += 1;
+        self.next = self.next.max(time.saturating_add(1));
+    }
+
+    // Remove a pending timestamp at `time`, once it has been served or cancelled.
+    fn remove(&mut self, time: u64) {
+        if let Some(count) = self.pending.get_mut(&time) {
+            *count -= 1;
+            if *count == 0 {
+                self.pending.remove(&time);
+            }
+        }
+    }
+}
+
+impl<T> Drop for Timestamp<T> {
+    fn drop(&mut self) {
+        if !self.served.load(Ordering::SeqCst) {
+            self.inner.state().remove(self.time);
+        }
+    }
+}
+
+impl<T> Drop for TimelineGuard<'_, T> {
+    fn drop(&mut self) {
+        let mut state = self.inner.state();
+        state.remove(self.time);
+        state.held = false;
+    }
+}
+
+impl<T> Clone for Timeline<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Deref for TimelineGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for TimelineGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
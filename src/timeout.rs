@@ -0,0 +1,134 @@
+use crate::{notified::TurnTimer, Guard, Sequex, SequexPoisoned};
+use std::{
+    fmt,
+    future::{poll_fn, Future},
+    pin::{pin, Pin},
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A timer future that resolves once a duration has passed, used by
+/// [Sequex::lock_async_timeout] to give up on a turn. Implement it for the sleep future of
+/// the runtime the tasks run on, such as a wrapper around `tokio::time::Sleep`, or use
+/// [ThreadDelay], which works on any executor.
+pub trait Delay: Future<Output = ()> {
+    /// Create a timer that resolves once `duration` has passed.
+    fn delay(duration: Duration) -> Self;
+}
+
+/// A [Delay] that sleeps on a thread of its own, for tasks that don't run on a runtime
+/// with a timer. The thread is spawned when the timer is first polled.
+pub struct ThreadDelay {
+    deadline: Option<Instant>,
+    waker: Option<Arc<Mutex<Waker>>>,
+}
+
+/// The error returned by [Sequex::lock_async_timeout].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimeoutError {
+    /// The turn didn't come before the timeout.
+    TimedOut,
+    /// The lock was poisoned or the sequence has finished.
+    Poisoned,
+}
+
+impl<T> Sequex<T> {
+    /// Wait for this ticket's turn and lock it, giving up once `timeout` has passed with a
+    /// timer of type `D`. Returns [TimeoutError::TimedOut] if the turn didn't come in time,
+    /// and [TimeoutError::Poisoned] if the lock was poisoned or the sequence has finished.
+    pub async fn lock_async_timeout<D: Delay>(
+        &self,
+        timeout: Duration,
+    ) -> Result<Guard<'_, T>, TimeoutError> {
+        let mut delay = pin!(D::delay(timeout));
+        let wait = self
+            .shared
+            .instrument_wait(self.ticket, "Sequex::lock_async_timeout");
+        let mut timer = TurnTimer::default();
+        poll_fn(|cx| {
+            let poll = self.poll_lock(cx, &mut timer);
+            if let Some(wait) = &wait {
+                wait.polled(poll.is_ready());
+            }
+            if let Poll::Ready(result) = poll {
+                return Poll::Ready(result.map_err(TimeoutError::from));
+            }
+            delay
+                .as_mut()
+                .poll(cx)
+                .map(|()| Err(TimeoutError::TimedOut))
+        })
+        .await
+    }
+}
+
+impl ThreadDelay {
+    // Returns when the timer fires, unless it never does.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+}
+
+impl Delay for ThreadDelay {
+    fn delay(duration: Duration) -> Self {
+        Self {
+            deadline: Instant::now().checked_add(duration),
+            waker: None,
+        }
+    }
+}
+
+impl Future for ThreadDelay {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        // A deadline too far away to be represented never passes.
+        let Some(deadline) = this.deadline else {
+            return Poll::Pending;
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Poll::Ready(());
+        }
+        match &this.waker {
+            Some(waker) => {
+                let mut waker = waker.lock().unwrap_or_else(PoisonError::into_inner);
+                if !waker.will_wake(cx.waker()) {
+                    waker.clone_from(cx.waker());
+                }
+            }
+            None => {
+                let waker = Arc::new(Mutex::new(cx.waker().clone()));
+                this.waker = Some(waker.clone());
+                thread::spawn(move || {
+                    thread::sleep(remaining);
+                    waker
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .wake_by_ref();
+                });
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl From<SequexPoisoned> for TimeoutError {
+    fn from(_: SequexPoisoned) -> Self {
+        Self::Poisoned
+    }
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TimedOut => write!(f, "timed out waiting for the turn"),
+            Self::Poisoned => write!(f, "sequex poisoned"),
+        }
+    }
+}
+
+impl std::error::Error for TimeoutError {}
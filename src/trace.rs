@@ -0,0 +1,188 @@
+use crate::Shared;
+use std::time::Instant;
+
+#[cfg(feature = "trace")]
+use crate::dump::Escaped;
+#[cfg(feature = "trace")]
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// A recording of the turns taken in the sequences it is attached to with
+/// [Builder::trace](crate::Builder::trace), and of the time tickets spent waiting for them,
+/// which can be exported in the trace event format read by `chrome://tracing` and Perfetto.
+/// Each sequence is shown as a process, with a thread per ticket. Only available with the
+/// `trace` feature.
+///
+/// Every turn is kept until the trace is cleared, so long running sequences should export
+/// and [clear](Trace::clear) it periodically.
+#[cfg(feature = "trace")]
+#[derive(Clone)]
+pub struct Trace {
+    state: Arc<Mutex<TraceState>>,
+}
+
+#[cfg(feature = "trace")]
+struct TraceState {
+    epoch: Instant,
+    sequences: Vec<String>,
+    spans: Vec<Span>,
+}
+
+// An interval a ticket spent holding or waiting for its turn.
+#[cfg(feature = "trace")]
+struct Span {
+    sequence: usize,
+    ticket: u64,
+    cycle: Option<u64>,
+    start: Instant,
+    end: Instant,
+}
+
+// The trace a sequence records into, and the turn it is holding.
+#[cfg(feature = "trace")]
+pub(crate) struct Tracer {
+    trace: Trace,
+    sequence: usize,
+    held: Mutex<Option<(u64, u64, Instant)>>,
+}
+
+#[cfg(feature = "trace")]
+impl Trace {
+    /// Create an empty trace, whose timestamps start now.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(TraceState {
+                epoch: Instant::now(),
+                sequences: Vec::new(),
+                spans: Vec::new(),
+            })),
+        }
+    }
+
+    /// Discard the turns recorded so far.
+    pub fn clear(&self) {
+        self.state().spans.clear();
+    }
+
+    /// Write the trace as a JSON object in the trace event format.
+    pub fn write_chrome_trace(&self, mut writer: impl Write) -> io::Result<()> {
+        let state = self.state();
+        let micros =
+            |instant: Instant| instant.saturating_duration_since(state.epoch).as_secs_f64() * 1e6;
+        write!(writer, "{{\"traceEvents\":[")?;
+        let mut separator = "";
+        for (pid, name) in state.sequences.iter().enumerate() {
+            write!(
+                writer,
+                "{separator}{{\"name\":\"process_name\",\"ph\":\"M\",\"pid\":{pid},\
+                 \"args\":{{\"name\":\"{}\"}}}}",
+                Escaped(name)
+            )?;
+            separator = ",";
+        }
+        for span in &state.spans {
+            let (name, args) = match span.cycle {
+                Some(cycle) => ("turn", format!("{{\"cycle\":{cycle}}}")),
+                None => ("wait", String::from("{}")),
+            };
+            write!(
+                writer,
+                "{separator}{{\"name\":\"{name}\",\"cat\":\"sequex\",\"ph\":\"X\",\
+                 \"ts\":{:.3},\"dur\":{:.3},\"pid\":{},\"tid\":{},\"args\":{args}}}",
+                micros(span.start),
+                micros(span.end) - micros(span.start),
+                span.sequence,
+                span.ticket,
+            )?;
+            separator = ",";
+        }
+        writeln!(writer, "],\"displayTimeUnit\":\"ms\"}}")?;
+        writer.flush()
+    }
+
+    // Add a sequence to the trace, returning the tracer it records with.
+    pub(crate) fn tracer(&self, name: String) -> Tracer {
+        let mut state = self.state();
+        state.sequences.push(name);
+        Tracer {
+            trace: self.clone(),
+            sequence: state.sequences.len() - 1,
+            held: Mutex::new(None),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, TraceState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(feature = "trace")]
+impl Default for Trace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "trace")]
+impl Tracer {
+    fn push(&self, ticket: u64, cycle: Option<u64>, start: Instant, end: Instant) {
+        self.trace.state().spans.push(Span {
+            sequence: self.sequence,
+            ticket,
+            cycle,
+            start,
+            end,
+        });
+    }
+
+    fn held(&self) -> MutexGuard<'_, Option<(u64, u64, Instant)>> {
+        self.held.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+impl<T> Shared<T> {
+    // Returns when a blocking acquisition started waiting, if the sequence is traced or keeps
+    // metrics.
+    pub(crate) fn wait_started(&self) -> Option<Instant> {
+        #[cfg(feature = "trace")]
+        if self.config.tracer.is_some() {
+            return Some(Instant::now());
+        }
+        self.measures_waits().then(Instant::now)
+    }
+
+    // Record the time `ticket` spent waiting for the turn it has just acquired, which ends
+    // as the turn starts.
+    pub(crate) fn trace_wait(&self, ticket: u64, since: Option<Instant>) {
+        #[cfg(feature = "trace")]
+        if let (Some(tracer), Some(since)) = (&self.config.tracer, since) {
+            let end = match *tracer.held() {
+                Some((held, _, start)) if held == ticket => start,
+                _ => Instant::now(),
+            };
+            tracer.push(ticket, None, since, end);
+        }
+    }
+
+    // Start recording the turn `ticket` has just acquired.
+    pub(crate) fn trace_acquire(&self, ticket: u64) {
+        #[cfg(feature = "trace")]
+        if let Some(tracer) = &self.config.tracer {
+            let cycle = self.cycle.load(std::sync::atomic::Ordering::SeqCst);
+            *tracer.held() = Some((ticket, cycle, Instant::now()));
+        }
+    }
+
+    // Record the held turn as it ends.
+    pub(crate) fn trace_release(&self) {
+        #[cfg(feature = "trace")]
+        if let Some(tracer) = &self.config.tracer {
+            if let Some((ticket, cycle, start)) = tracer.held().take() {
+                tracer.push(ticket, Some(cycle), start, Instant::now());
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{Guard, Sequex, SequexPoisoned};
+use std::ops::{Deref, DerefMut};
+
+/// A turn that works on a copy of the value and only writes it back on
+/// [Transaction::commit]. Dropping the transaction without committing, including on an
+/// early return or a panic, discards the changes and passes the turn on with the value
+/// untouched. Created with [Sequex::transaction] or [Guard::into_transaction].
+pub struct Transaction<'a, T> {
+    guard: Guard<'a, T>,
+    working: T,
+}
+
+impl<T: Clone> Sequex<T> {
+    /// Take this ticket's turn, blocking until it comes, and start a transaction on a copy of
+    /// the value. Returns [SequexPoisoned] if the lock was poisoned or the sequence has
+    /// finished.
+    pub fn transaction(&self) -> Result<Transaction<'_, T>, SequexPoisoned> {
+        self.lock().map(Guard::into_transaction)
+    }
+}
+
+impl<'a, T: Clone> Guard<'a, T> {
+    /// Start a transaction on a copy of the value, holding on to this turn.
+    pub fn into_transaction(self) -> Transaction<'a, T> {
+        let working = T::clone(&self);
+        Transaction {
+            guard: self,
+            working,
+        }
+    }
+}
+
+impl<T> Transaction<'_, T> {
+    /// Returns the value as it was when the transaction started.
+    pub fn original(&self) -> &T {
+        &self.guard
+    }
+
+    /// Write the working copy back to the value and release the turn.
+    pub fn commit(self) {
+        let Self { mut guard, working } = self;
+        *guard = working;
+    }
+
+    /// Discard the working copy and release the turn, leaving the value untouched.
+    pub fn abort(self) {}
+}
+
+impl<T> Deref for Transaction<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.working
+    }
+}
+
+impl<T> DerefMut for Transaction<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.working
+    }
+}
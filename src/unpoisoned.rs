@@ -0,0 +1,66 @@
+use crate::{Guard, Sequex};
+use std::ops::Deref;
+
+/// A ticket of a sequence built without poisoning, created with
+/// [Builder::build_unpoisoned](crate::Builder::build_unpoisoned). Since dropped tickets and
+/// panicking holders don't poison the lock, guards are returned directly. Derefs to the
+/// underlying [Sequex] for the rest of its API.
+///
+/// The sequence can still end, in which case locking panics. It ends once it finishes, is
+/// handed to a successor, or is poisoned anyway: if its lazily initialized value fails to
+/// construct, a hook panics, a ticket calls [Sequex::poison] or foreign code poisons it
+/// through the C API, a turn overruns its time slice under `OverrunPolicy::Poison`, a fault
+/// is injected, or its state is found corrupted. See [PoisonReason](crate::PoisonReason).
+pub struct Unpoisoned<T> {
+    sequex: Sequex<T>,
+}
+
+const ENDED: &str = "sequence has ended";
+
+// The accessors panic by design, in place of returning `SequexPoisoned`.
+#[allow(clippy::expect_used)]
+impl<T> Unpoisoned<T> {
+    pub(crate) fn new(sequex: Sequex<T>) -> Self {
+        Self { sequex }
+    }
+
+    /// Attempt to acquire the lock, without blocking the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sequence has ended, for any of the reasons listed on [Unpoisoned].
+    pub fn try_lock(&self) -> Option<Guard<'_, T>> {
+        self.sequex.try_lock().expect(ENDED)
+    }
+
+    /// Acquire the lock, blocking the current thread until it is this ticket's turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sequence has ended, for any of the reasons listed on [Unpoisoned].
+    pub fn lock(&self) -> Guard<'_, T> {
+        self.sequex.lock().expect(ENDED)
+    }
+
+    /// Acquire the lock and run `f` on the value. If `f` panics, the turn is passed on and
+    /// the panic resumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sequence has ended, for any of the reasons listed on [Unpoisoned].
+    pub fn lock_with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        self.sequex.lock_with(f).expect(ENDED)
+    }
+
+    /// Returns the underlying ticket.
+    pub fn into_inner(self) -> Sequex<T> {
+        self.sequex
+    }
+}
+
+impl<T> Deref for Unpoisoned<T> {
+    type Target = Sequex<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.sequex
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{SequexPoisoned, Shared};
+use std::time::Duration;
+
+// The parts of taking and releasing a turn that belong to the extensions of the `sync`
+// feature, which have nothing to do in builds without them.
+impl<T> Shared<T> {
+    pub(crate) fn start_pace(&self) {}
+
+    pub(crate) fn start_scheduled(&self, _ticket: u64) {}
+
+    pub(crate) fn start_delay(&self, _ticket: u64) -> Option<Duration> {
+        None
+    }
+
+    pub(crate) fn pass_baton(&self) {}
+
+    pub(crate) fn deliver_mail(&self, _ticket: u64) {}
+
+    pub(crate) fn send_broadcasts(&self) {}
+
+    pub(crate) fn pi_acquire(&self) {}
+
+    pub(crate) fn pi_release(&self) {}
+
+    pub(crate) fn wait_for_holder(&self) -> bool {
+        false
+    }
+
+    // The holder is only tracked for the misuse checks of debug builds.
+    pub(crate) fn set_holder(&self, ticket: Option<u64>) {
+        self.set_locked_by(ticket);
+    }
+
+    pub(crate) fn start_slice(&self, _ticket: u64) {}
+
+    pub(crate) fn check_slice(&self) {}
+
+    pub(crate) fn end_slice(&self) -> bool {
+        false
+    }
+
+    pub(crate) fn slice_passed(&self) -> bool {
+        false
+    }
+
+    pub(crate) unsafe fn run_acquire_hooks(
+        &self,
+        _ticket: u64,
+        _read_only: bool,
+    ) -> Result<bool, SequexPoisoned> {
+        Ok(false)
+    }
+
+    pub(crate) unsafe fn run_release_hooks(
+        &self,
+        _ticket: u64,
+        _read_only: bool,
+    ) -> Result<bool, SequexPoisoned> {
+        Ok(false)
+    }
+
+    pub(crate) fn quorum_blocks(&self, _ticket: u64, _member: u64) -> bool {
+        false
+    }
+
+    pub(crate) fn reach_quorum(&self, _ticket: u64, _member: u64, _panicked: bool) -> bool {
+        true
+    }
+
+    pub(crate) fn leave_group(&self, _ticket: u64) -> bool {
+        false
+    }
+
+    pub(crate) unsafe fn save_rollback(&self) {}
+
+    pub(crate) unsafe fn roll_back(&self) -> bool {
+        false
+    }
+
+    pub(crate) unsafe fn take_rollback(&self) -> Option<T> {
+        None
+    }
+
+    pub(crate) fn rolls_back(&self) -> bool {
+        false
+    }
+
+    pub(crate) fn heals(&self) -> bool {
+        false
+    }
+
+    pub(crate) unsafe fn heal_now(&self) {}
+
+    pub(crate) fn heal_later(&self) {}
+
+    pub(crate) unsafe fn heal_pending(&self) {}
+
+    pub(crate) fn next_turn(&self, ticket: u64, num_tickets: u64) -> (u64, bool) {
+        self.next_ticket(ticket, num_tickets)
+    }
+
+    pub(crate) fn deferred_tickets(&self) -> Vec<u64> {
+        Vec::new()
+    }
+
+    pub(crate) fn save_history(&self) {}
+
+    pub(crate) fn send_snapshots(&self) {}
+
+    pub(crate) fn apply_swaps(&self) {}
+
+    pub(crate) fn publish(&self, _version: u64) {}
+}
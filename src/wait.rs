@@ -0,0 +1,119 @@
+use crate::{adaptive::HoldTimes, SequexPoisoned};
+use std::{
+    hint,
+    sync::atomic::AtomicU64,
+    time::{Duration, Instant},
+};
+
+/// How a blocking acquisition waits for its turn. Configured with [Builder::wait_strategy].
+///
+/// On wasm32 with the `atomics` target feature, a thread that parks waits with
+/// `memory.atomic.wait64` and is woken by `memory.atomic.notify` when the turn changes.
+/// Browsers do not allow waiting on the main thread, which should use [Sequex::try_lock] or
+/// [WaitStrategy::Spin] instead. Without the `atomics` feature there are no other threads to
+/// wait on, and blocking degrades to polling.
+///
+/// [Sequex::try_lock]: crate::Sequex::try_lock
+/// [Builder::wait_strategy]: crate::Builder::wait_strategy
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WaitStrategy {
+    /// Measure how long turns are held for, and spin while waiting for turns that are held
+    /// for less than a microsecond, park straight away for turns that are held for longer
+    /// than 100µs, and spin for about one turn before parking for the ones in between.
+    /// Spinning for short turns gives up after 100µs, in case the holder was descheduled.
+    /// Until the first turn has been measured, and where turns aren't measured, such as in
+    /// [lock_all](crate::lock_all), this waits like [WaitStrategy::Backoff].
+    #[default]
+    Adaptive,
+    /// Park the thread between attempts, with an exponential backoff of up to 10ms.
+    Backoff,
+    /// Busy-wait in bounded bursts of spinning, without ever parking or sleeping the thread,
+    /// including for turns held up by a schedule or pacing. This is suitable for real-time
+    /// threads such as audio callbacks, where being descheduled is unacceptable, as long as
+    /// every ticket holder runs on its own core. See
+    /// [Builder::wait_strategy](crate::Builder::wait_strategy) for what taking a turn may
+    /// still do besides waiting.
+    Spin,
+}
+
+// The most spin loop hints issued between two attempts under `WaitStrategy::Spin`.
+pub(crate) const MAX_SPINS: u32 = 1 << 6;
+
+// The longest a thread parks between two attempts under `WaitStrategy::Backoff`, in
+// microseconds.
+pub(crate) const MAX_BACKOFF: u64 = 10_000;
+
+// Retry a non-blocking acquisition until it succeeds. If the acquisition depends on a single
+// state word, passing it allows waiting on it directly where the platform supports it, and
+// passing the hold times of the turns it waits for lets `WaitStrategy::Adaptive` adapt.
+pub(crate) fn block<R>(
+    strategy: WaitStrategy,
+    current: Option<&AtomicU64>,
+    hold_times: Option<&HoldTimes>,
+    mut try_acquire: impl FnMut() -> Result<Option<R>, SequexPoisoned>,
+) -> Result<R, SequexPoisoned> {
+    let (spin_for, mut backoff) = match (strategy, hold_times) {
+        (WaitStrategy::Adaptive, Some(hold_times)) => hold_times.plan(),
+        _ => (Duration::ZERO, 100),
+    };
+    let spin_until = (!spin_for.is_zero()).then(|| Instant::now() + spin_for);
+    let mut spins = 1;
+    loop {
+        if let Some(acquired) = try_acquire()? {
+            return Ok(acquired);
+        }
+        match strategy {
+            WaitStrategy::Adaptive if spin_until.is_some_and(|until| Instant::now() < until) => {
+                for _ in 0..spins {
+                    hint::spin_loop();
+                }
+                spins = (spins * 2).min(MAX_SPINS);
+            }
+            WaitStrategy::Adaptive | WaitStrategy::Backoff => {
+                park(current, Duration::from_micros(backoff));
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+            WaitStrategy::Spin => {
+                for _ in 0..spins {
+                    hint::spin_loop();
+                }
+                spins = (spins * 2).min(MAX_SPINS);
+            }
+        }
+    }
+}
+
+// Park the current thread until the timeout elapses, or it is woken early.
+#[cfg(not(all(target_arch = "wasm32", target_feature = "atomics")))]
+fn park(_current: Option<&AtomicU64>, timeout: Duration) {
+    std::thread::park_timeout(timeout);
+}
+
+// Wait for the state word to change, so that `notify` can wake the thread early.
+#[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+fn park(current: Option<&AtomicU64>, timeout: Duration) {
+    use std::{arch::wasm32, sync::atomic::Ordering};
+    match current {
+        Some(current) => unsafe {
+            let observed = current.load(Ordering::SeqCst);
+            wasm32::memory_atomic_wait64(
+                current.as_ptr().cast(),
+                observed as i64,
+                timeout.as_nanos() as i64,
+            );
+        },
+        None => std::thread::park_timeout(timeout),
+    }
+}
+
+// Wake every thread waiting on a state word that has changed.
+#[cfg(not(all(target_arch = "wasm32", target_feature = "atomics")))]
+pub(crate) fn notify(_current: &AtomicU64) {}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
+pub(crate) fn notify(current: &AtomicU64) {
+    unsafe {
+        std::arch::wasm32::memory_atomic_notify(current.as_ptr().cast(), u32::MAX);
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    task::{Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+// A set of tasks or threads waiting for the shared state to change, and how many there are,
+// so that waking an empty set takes no lock.
+#[derive(Default)]
+pub(crate) struct Waiters {
+    count: AtomicUsize,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl Waiters {
+    // Register a waker, unless it would wake the same task as one already registered. The
+    // caller must re-check its condition after registering to avoid missing a wake up.
+    #[cfg_attr(
+        not(any(feature = "sync", feature = "async", feature = "pipeline")),
+        allow(dead_code)
+    )]
+    pub(crate) fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+        if !wakers.iter().any(|registered| registered.will_wake(waker)) {
+            wakers.push(waker.clone());
+            self.count.store(wakers.len(), Ordering::SeqCst);
+        }
+    }
+
+    // Returns the number of tasks or threads registered.
+    pub(crate) fn len(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    // Wake everything that was registered.
+    pub(crate) fn wake_all(&self) {
+        if self.count.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+        let mut registered = self.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+        let wakers = mem::take(&mut *registered);
+        self.count.store(0, Ordering::SeqCst);
+        drop(registered);
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+// Wakes a thread parked in `block_on`.
+#[cfg_attr(not(any(feature = "sync", feature = "pipeline")), allow(dead_code))]
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// Drive a poll function to completion on the current thread, parking between polls.
+#[cfg_attr(not(any(feature = "sync", feature = "pipeline")), allow(dead_code))]
+pub(crate) fn block_on<R>(mut poll: impl FnMut(&Waker) -> Poll<R>) -> R {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    loop {
+        match poll(&waker) {
+            Poll::Ready(result) => return result,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Turns that are yielded and re-queued at the end of the cycle.
+
+#![cfg(feature = "sync")]
+
+use sequex::Sequex;
+
+#[test]
+fn yielded_turn_is_served_after_the_last_ticket() {
+    let [first, second] = Sequex::new_array(Vec::new());
+    assert!(first.lock().unwrap().yield_turn().is_none());
+    second.lock().unwrap().push(1);
+    let mut guard = first.lock().unwrap();
+    guard.push(0);
+    assert_eq!(guard.cycle(), 0);
+    drop(guard);
+    let guard = first.lock().unwrap();
+    assert_eq!(*guard, [1, 0]);
+    assert_eq!(guard.cycle(), 1);
+}
+
+#[test]
+fn last_ticket_keeps_its_turn_when_yielding_to_nobody() {
+    let [first, second] = Sequex::new_array(0u32);
+    drop(first.lock().unwrap());
+    let mut guard = second.lock().unwrap().yield_turn().unwrap();
+    *guard += 1;
+    assert_eq!(guard.cycle(), 0);
+    drop(guard);
+    let guard = first.lock().unwrap();
+    assert_eq!(*guard, 1);
+    assert_eq!(guard.cycle(), 1);
+}
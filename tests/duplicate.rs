@@ -0,0 +1,33 @@
+//! Duplicate handles to a single position in the turn order.
+
+#![cfg(feature = "sync")]
+
+use sequex::{DuplicateError, Sequex};
+use std::{thread, time::Duration};
+
+#[test]
+fn locking_a_held_position_is_an_error() {
+    let [first, second] = Sequex::new_array(0u32);
+    let [a, b] = first.duplicate();
+    let mut guard = a.lock().unwrap();
+    *guard += 1;
+    assert!(matches!(b.lock(), Err(DuplicateError::Held)));
+    assert!(matches!(b.try_lock(), Err(DuplicateError::Held)));
+    drop(guard);
+    assert_eq!(*second.lock().unwrap(), 1);
+    assert_eq!(*b.lock().unwrap(), 1);
+}
+
+#[test]
+fn waiting_handle_claims_the_position() {
+    let [first, second] = Sequex::new_array(0u32);
+    let [a, b] = second.duplicate();
+    thread::scope(|scope| {
+        let waiter = scope.spawn(|| *a.lock().unwrap());
+        thread::sleep(Duration::from_millis(20));
+        assert!(matches!(b.try_lock(), Err(DuplicateError::Held)));
+        *first.lock().unwrap() = 5;
+        assert_eq!(waiter.join().unwrap(), 5);
+    });
+    assert!(b.try_lock().unwrap().is_none());
+}
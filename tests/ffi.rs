@@ -0,0 +1,113 @@
+//! The C API, driven from Rust the way foreign code would drive it.
+
+#![cfg(feature = "ffi")]
+
+use sequex::{
+    ffi::{
+        into_ffi, sequex_create, sequex_guard_data, sequex_lock, sequex_poison, sequex_ticket_free,
+        sequex_try_lock, sequex_unlock, SequexGuard, SequexTicket, SEQUEX_INVALID, SEQUEX_OK,
+        SEQUEX_PANICKED, SEQUEX_POISONED, SEQUEX_WOULD_BLOCK,
+    },
+    Sequex,
+};
+use std::{ffi::c_void, ptr};
+
+// Create a sequence of two tickets over `data`.
+fn create(data: *mut c_void) -> [*mut SequexTicket; 2] {
+    let mut tickets = [ptr::null_mut(); 2];
+    assert_eq!(
+        unsafe { sequex_create(data, 2, tickets.as_mut_ptr()) },
+        SEQUEX_OK
+    );
+    tickets
+}
+
+#[test]
+fn create_lock_and_free() {
+    let mut counter = 0u32;
+    let [first, second] = create(ptr::addr_of_mut!(counter).cast());
+    unsafe {
+        let mut guard: *mut SequexGuard = ptr::null_mut();
+        assert_eq!(sequex_try_lock(second, &mut guard), SEQUEX_WOULD_BLOCK);
+        assert_eq!(sequex_lock(first, &mut guard), SEQUEX_OK);
+        *sequex_guard_data(guard).cast::<u32>() += 1;
+        sequex_unlock(guard);
+        assert_eq!(sequex_try_lock(first, &mut guard), SEQUEX_WOULD_BLOCK);
+        assert_eq!(sequex_try_lock(second, &mut guard), SEQUEX_OK);
+        *sequex_guard_data(guard).cast::<u32>() += 1;
+        sequex_unlock(guard);
+        sequex_ticket_free(first);
+        sequex_ticket_free(second);
+    }
+    assert_eq!(counter, 2);
+}
+
+#[test]
+fn freeing_a_ticket_poisons_the_others() {
+    let [first, second] = create(ptr::null_mut());
+    unsafe {
+        sequex_ticket_free(first);
+        let mut guard = ptr::null_mut();
+        assert_eq!(sequex_lock(second, &mut guard), SEQUEX_POISONED);
+        sequex_ticket_free(second);
+    }
+}
+
+#[test]
+fn poisoning_fails_every_ticket() {
+    let [first, second] = create(ptr::null_mut());
+    unsafe {
+        sequex_poison(second);
+        let mut guard = ptr::null_mut();
+        assert_eq!(sequex_try_lock(first, &mut guard), SEQUEX_POISONED);
+        sequex_ticket_free(first);
+        sequex_ticket_free(second);
+    }
+}
+
+#[test]
+fn null_arguments_are_rejected() {
+    let mut guard = ptr::null_mut();
+    unsafe {
+        assert_eq!(
+            sequex_create(ptr::null_mut(), 2, ptr::null_mut()),
+            SEQUEX_INVALID
+        );
+        assert_eq!(sequex_lock(ptr::null(), &mut guard), SEQUEX_INVALID);
+        assert_eq!(sequex_try_lock(ptr::null(), &mut guard), SEQUEX_INVALID);
+        assert!(sequex_guard_data(ptr::null()).is_null());
+        sequex_unlock(ptr::null_mut());
+        sequex_poison(ptr::null());
+        sequex_ticket_free(ptr::null_mut());
+    }
+}
+
+#[test]
+fn existing_tickets_lock_their_value() {
+    let [rust, c] = Sequex::new_array(1u64);
+    let c = into_ffi(c);
+    drop(rust.lock().unwrap());
+    unsafe {
+        let mut guard = ptr::null_mut();
+        assert_eq!(sequex_lock(c, &mut guard), SEQUEX_OK);
+        *sequex_guard_data(guard).cast::<u64>() = 5;
+        sequex_unlock(guard);
+    }
+    assert_eq!(*rust.lock().unwrap(), 5);
+    unsafe { sequex_ticket_free(c) };
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn panics_are_caught() {
+    let [first, second] = create(ptr::null_mut());
+    unsafe {
+        let mut guard = ptr::null_mut();
+        assert_eq!(sequex_lock(first, &mut guard), SEQUEX_OK);
+        let mut again = ptr::null_mut();
+        assert_eq!(sequex_lock(first, &mut again), SEQUEX_PANICKED);
+        sequex_unlock(guard);
+        sequex_ticket_free(first);
+        sequex_ticket_free(second);
+    }
+}
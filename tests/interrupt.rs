@@ -0,0 +1,31 @@
+//! Waits for a turn that are given up once an interrupt flag is raised.
+
+#![cfg(feature = "sync")]
+
+use sequex::{InterruptError, Sequex};
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn paced_turn_is_interrupted_while_it_sleeps() {
+    let [first, second] = Sequex::builder((), 2)
+        .min_interval(Duration::from_secs(30))
+        .build_array();
+    drop(first.lock().unwrap());
+    let interrupted = AtomicBool::new(false);
+    let start = Instant::now();
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(20));
+            interrupted.store(true, Ordering::SeqCst);
+        });
+        assert!(matches!(
+            second.lock_interruptible(&interrupted),
+            Err(InterruptError::Interrupted)
+        ));
+    });
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,17 @@
+//! Sequences whose value is constructed when the lock is first acquired.
+
+use sequex::Sequex;
+use std::{thread, time::Duration};
+
+#[test]
+fn snapshots_wait_for_the_first_turn_to_construct_the_value() {
+    let mut tickets = Sequex::new_with(|| thread::current().id(), 2).into_iter();
+    let (first, second) = (tickets.next().unwrap(), tickets.next().unwrap());
+    thread::scope(|scope| {
+        let snapshot = scope.spawn(|| second.snapshot().unwrap());
+        thread::sleep(Duration::from_millis(20));
+        let builder = *first.lock().unwrap();
+        assert_eq!(builder, thread::current().id());
+        assert_eq!(snapshot.join().unwrap(), builder);
+    });
+}
@@ -0,0 +1,48 @@
+//! Metrics of sequences, exported in the Prometheus text exposition format.
+
+#![cfg(feature = "metrics")]
+
+use sequex::{Metrics, Sequex};
+
+fn encode(metrics: &Metrics) -> String {
+    let mut encoded = Vec::new();
+    metrics.encode_prometheus(&mut encoded).unwrap();
+    String::from_utf8(encoded).unwrap()
+}
+
+#[test]
+fn sequences_sharing_a_name_are_one_series() {
+    let metrics = Metrics::new();
+    let [a] = Sequex::builder((), 1)
+        .metrics(&metrics, "stage")
+        .build_array();
+    let [b] = Sequex::builder((), 1)
+        .metrics(&metrics, "stage")
+        .build_array();
+    drop(a.lock().unwrap());
+    drop(b.lock().unwrap());
+    drop(b.lock().unwrap());
+    let encoded = encode(&metrics);
+    let turns: Vec<_> = encoded
+        .lines()
+        .filter(|line| line.starts_with("sequex_turns_total{"))
+        .collect();
+    assert_eq!(turns, ["sequex_turns_total{sequence=\"stage\"} 3"]);
+    assert!(encoded.contains("sequex_hold_seconds_count{sequence=\"stage\"} 3"));
+}
+
+#[test]
+fn dropped_sequences_are_no_longer_exported() {
+    let metrics = Metrics::new();
+    let [kept] = Sequex::builder((), 1)
+        .metrics(&metrics, "kept")
+        .build_array();
+    let [dropped] = Sequex::builder((), 1)
+        .metrics(&metrics, "dropped")
+        .build_array();
+    drop(dropped);
+    let encoded = encode(&metrics);
+    assert!(encoded.contains("sequence=\"kept\""));
+    assert!(!encoded.contains("sequence=\"dropped\""));
+    drop(kept);
+}
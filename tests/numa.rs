@@ -0,0 +1,19 @@
+//! Sequences whose ticket word is placed on a NUMA node.
+
+#![cfg(feature = "sync")]
+
+use sequex::Sequex;
+
+#[test]
+fn placed_sequence_takes_turns() {
+    let [first, second] = Sequex::builder(0u32, 2).numa_node(0).build_array();
+    *first.lock().unwrap() += 1;
+    assert_eq!(*second.lock().unwrap(), 1);
+}
+
+#[test]
+fn nodes_the_system_cant_have_are_ignored() {
+    let [first, second] = Sequex::builder(0u32, 2).numa_node(u32::MAX).build_array();
+    *first.lock().unwrap() += 1;
+    assert_eq!(*second.lock().unwrap(), 1);
+}
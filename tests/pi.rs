@@ -0,0 +1,36 @@
+//! Tickets of a sequence backed by a priority-inheriting futex, taking turns from several
+//! threads.
+
+#![cfg(feature = "sync")]
+
+use sequex::{Sequex, SequexPoisoned};
+use std::thread;
+
+#[test]
+fn turns_pass_between_threads() {
+    let tickets = Sequex::builder(Vec::new(), 3).build_priority_inheriting();
+    thread::scope(|scope| {
+        for ticket in &tickets {
+            scope.spawn(move || {
+                for _ in 0..100 {
+                    ticket.lock().unwrap().push(ticket.ticket());
+                }
+            });
+        }
+    });
+    let order = tickets[0].lock_with(|order| order.clone()).unwrap();
+    assert_eq!(order.len(), 300);
+    assert!(order.iter().zip((0..3).cycle()).all(|(&a, b)| a == b));
+}
+
+#[test]
+fn poisoning_wakes_blocked_tickets() {
+    let tickets = Sequex::builder((), 2).build_priority_inheriting();
+    let guard = tickets[0].lock().unwrap();
+    thread::scope(|scope| {
+        let waiter = scope.spawn(|| tickets[1].lock().map(drop));
+        tickets[0].poison();
+        drop(guard);
+        assert!(matches!(waiter.join().unwrap(), Err(SequexPoisoned)));
+    });
+}
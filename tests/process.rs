@@ -0,0 +1,161 @@
+//! Tickets of a `ProcessSequex` attached from separate processes, over a file that each of them
+//! maps. The other process is this test binary again, running one of the `child_` tests.
+
+#![cfg(all(feature = "sync", target_os = "linux"))]
+
+use sequex::{ProcessSequex, SequexPoisoned};
+use std::{
+    env,
+    ffi::{c_int, c_long, c_void},
+    fs::{self, OpenOptions},
+    os::fd::AsRawFd,
+    path::{Path, PathBuf},
+    process,
+    process::{Command, ExitStatus, Stdio},
+    ptr,
+    time::Duration,
+};
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        fd: c_int,
+        offset: c_long,
+    ) -> *mut c_void;
+}
+
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_SHARED: c_int = 1;
+
+// Names the region file in the environment of a child process.
+const REGION: &str = "SEQUEX_TEST_REGION";
+
+fn region_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("sequex-{name}-{}", std::process::id()))
+}
+
+// Map the region file at `path` into this process. The mapping outlives the file, and is
+// never unmapped.
+fn map(path: &Path) -> (*mut u8, usize) {
+    let len = ProcessSequex::<u64>::region_len();
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .unwrap();
+    file.set_len(len as u64).unwrap();
+    let region = unsafe {
+        mmap(
+            ptr::null_mut(),
+            len,
+            PROT_READ | PROT_WRITE,
+            MAP_SHARED,
+            file.as_raw_fd(),
+            0,
+        )
+    };
+    assert_ne!(region as isize, -1, "mmap failed");
+    (region.cast(), len)
+}
+
+// Run the test `child` in a new process, over the region at `path`.
+fn spawn(child: &str, path: &Path) -> ExitStatus {
+    Command::new(env::current_exe().unwrap())
+        .args([child, "--exact", "--test-threads=1"])
+        .env(REGION, path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap()
+}
+
+// Attach to the second ticket of the region this process was spawned over, if it was.
+fn attach_child() -> Option<ProcessSequex<u64>> {
+    let path = env::var_os(REGION)?;
+    let (region, len) = map(Path::new(&path));
+    Some(unsafe { ProcessSequex::attach(region, len, 1) }.unwrap())
+}
+
+#[test]
+fn child_takes_turn() {
+    let Some(sequex) = attach_child() else {
+        return;
+    };
+    let mut guard = sequex.lock().unwrap();
+    assert_eq!(*guard, 1);
+    *guard = 2;
+}
+
+#[test]
+fn child_panics_holding_turn() {
+    let Some(sequex) = attach_child() else {
+        return;
+    };
+    let _guard = sequex.lock().unwrap();
+    panic!("holder died");
+}
+
+#[test]
+fn child_dies_holding_turn() {
+    let Some(sequex) = attach_child() else {
+        return;
+    };
+    let guard = sequex.lock().unwrap();
+    std::mem::forget(guard);
+    process::exit(0);
+}
+
+#[test]
+fn turns_pass_between_processes() {
+    let path = region_path("turns");
+    let (region, len) = map(&path);
+    unsafe { ProcessSequex::create(region, len, 0u64, 2) }.unwrap();
+    let sequex = unsafe { ProcessSequex::<u64>::attach(region, len, 0) }.unwrap();
+    *sequex.lock().unwrap() = 1;
+    let status = spawn("child_takes_turn", &path);
+    fs::remove_file(&path).unwrap();
+    assert!(status.success());
+    let guard = sequex.lock().unwrap();
+    assert_eq!(*guard, 2);
+    assert_eq!(guard.cycle(), 1);
+}
+
+#[test]
+fn panicking_holder_poisons_every_process() {
+    let path = region_path("poison");
+    let (region, len) = map(&path);
+    unsafe { ProcessSequex::create(region, len, 0u64, 2) }.unwrap();
+    let sequex = unsafe { ProcessSequex::<u64>::attach(region, len, 0) }.unwrap();
+    *sequex.lock().unwrap() = 1;
+    let status = spawn("child_panics_holding_turn", &path);
+    fs::remove_file(&path).unwrap();
+    assert!(!status.success());
+    assert!(matches!(sequex.lock(), Err(SequexPoisoned)));
+    assert!(matches!(sequex.try_lock(), Err(SequexPoisoned)));
+}
+
+#[test]
+fn turn_of_a_dead_holder_is_abandoned_and_recovered() {
+    let path = region_path("abandon");
+    let (region, len) = map(&path);
+    unsafe { ProcessSequex::create(region, len, 0u64, 2) }.unwrap();
+    let sequex = unsafe { ProcessSequex::<u64>::attach(region, len, 0) }
+        .unwrap()
+        .abandon_after(Duration::from_millis(50));
+    *sequex.lock().unwrap() = 1;
+    let status = spawn("child_dies_holding_turn", &path);
+    fs::remove_file(&path).unwrap();
+    assert!(status.success());
+    assert!(matches!(sequex.lock(), Err(SequexPoisoned)));
+    assert!(sequex.is_abandoned());
+    *sequex.recover().unwrap() = 3;
+    let guard = sequex.lock().unwrap();
+    assert_eq!(*guard, 3);
+    assert_eq!(guard.cycle(), 1);
+}
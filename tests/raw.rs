@@ -0,0 +1,44 @@
+//! Tickets and guards converted into raw pointers and back, as when they are parked in the
+//! context of a C callback.
+
+use sequex::{Guard, Sequex};
+use std::thread;
+
+#[test]
+fn ticket_round_trips_through_a_pointer() {
+    let [first, second] = Sequex::new_array(0u32);
+    let raw = first.into_raw();
+    let first = unsafe { Sequex::from_raw(raw) };
+    assert_eq!(first.ticket(), 0);
+    *first.lock().unwrap() += 1;
+    assert_eq!(*second.lock().unwrap(), 1);
+}
+
+#[test]
+fn parked_turn_passes_on_once_resumed() {
+    let [first, second] = Sequex::new_array(0u32);
+    let guard = first.lock().unwrap();
+    let version = guard.version();
+    let raw = guard.into_raw();
+    assert!(second.try_lock().unwrap().is_none());
+    let raw = raw as usize;
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            let mut guard = unsafe { Guard::from_raw(raw as *const Sequex<u32>) };
+            *guard = 7;
+        });
+    });
+    let guard = second.try_lock().unwrap().unwrap();
+    assert_eq!(*guard, 7);
+    assert_eq!(guard.version(), version + 1);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "locked again")]
+fn resumed_turn_is_held_by_the_thread_again() {
+    let [first, _second] = Sequex::new_array(());
+    let raw = first.lock().unwrap().into_raw();
+    let _resumed = unsafe { Guard::from_raw(raw) };
+    let _ = first.lock();
+}
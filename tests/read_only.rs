@@ -0,0 +1,13 @@
+//! Read-only tickets, which take their turns without changing the value.
+
+use sequex::Sequex;
+
+#[test]
+fn lock_with_leaves_the_version_unchanged() {
+    let [writer, reader] = Sequex::new_array(1u32);
+    *writer.lock().unwrap() = 2;
+    let reader = reader.into_read_only();
+    let version = writer.version();
+    assert_eq!(reader.lock_with(|value| *value).unwrap(), 2);
+    assert_eq!(writer.version(), version);
+}
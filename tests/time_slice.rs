@@ -0,0 +1,37 @@
+//! Turns held for longer than the time slice of their sequence.
+
+#![cfg(feature = "sync")]
+
+use sequex::{OverrunPolicy, Sequex};
+use std::{thread, time::Duration};
+
+#[test]
+fn passing_overrun_skips_the_rest_of_the_cycle() {
+    let [first, second, third] = Sequex::builder(0u32, 3)
+        .time_slice(Duration::from_millis(1), |_| OverrunPolicy::Pass)
+        .build_array();
+    {
+        let mut guard = first.lock().unwrap();
+        *guard += 1;
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(second.try_lock().unwrap().is_none());
+    assert!(third.try_lock().unwrap().is_none());
+    let guard = first.try_lock().unwrap().unwrap();
+    assert_eq!(*guard, 1);
+    assert_eq!(guard.cycle(), 1);
+    drop(guard);
+    assert!(second.try_lock().unwrap().is_some());
+}
+
+#[test]
+fn warning_overrun_passes_the_turn_on_as_usual() {
+    let [first, second] = Sequex::builder((), 2)
+        .time_slice(Duration::from_millis(1), |_| OverrunPolicy::Warn)
+        .build_array();
+    {
+        let _guard = first.lock().unwrap();
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(second.try_lock().unwrap().is_some());
+}